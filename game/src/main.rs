@@ -1,7 +1,7 @@
 #[macro_use]
 extern crate log;
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use amethyst::{
     assets::{PrefabLoaderSystem, Processor},
@@ -29,7 +29,7 @@ use amethyst::{
     window::{ScreenDimensions, Window, WindowBundle},
 };
 
-use game_physics::PhysicsBundle;
+use game_physics::{CollisionLayers, PhysicsBundle};
 
 use crate::states::{GamePrefabData, LoadingState};
 
@@ -67,6 +67,12 @@ fn main() -> amethyst::Result<()> {
     // key bindings
     let key_bindings_path = app_root.join("resources/input.ron");
 
+    // collision layers
+    let collision_layers = CollisionLayers::load(app_root.join("resources/collision_layers.ron"));
+
+    // physics materials
+    let physics_materials_path = app_root.join("resources/physics_materials.ron");
+
     let game_data = GameDataBuilder::default()
         .with_bundle(WindowBundle::from_config_path(display_config_path))?
         .with_bundle(TransformBundle::new())?
@@ -74,7 +80,13 @@ fn main() -> amethyst::Result<()> {
             InputBundle::<StringBindings>::new().with_bindings_from_file(key_bindings_path)?,
         )?
         .with_bundle(UiBundle::<DefaultBackend, StringBindings>::new())?
-        .with_bundle(PhysicsBundle::default().with_debug_lines())? // TODO: move to custom game data?!
+        .with_bundle(
+            PhysicsBundle::<f32>::default()
+                .with_debug_lines()
+                .with_collision_layers(collision_layers)
+                .with_materials_from_file(physics_materials_path)
+                .with_stats_log(Duration::from_secs(5)),
+        )? // TODO: move to custom game data?!
         .with(
             Processor::<SpriteSheet>::new(),
             "sprite_sheet_processor",
@@ -16,6 +16,7 @@ use amethyst::{
 use game_physics::{
     body::BodyStatus,
     math::Isometry3,
+    AutoCollider,
     PhysicsBodyBuilder,
     PhysicsColliderBuilder,
     Shape,
@@ -132,8 +133,11 @@ impl<'a, 'b> GameState<'a, 'b> {
                 sprite_sheet: self.character_handle.clone(),
                 sprite_number: 0,
             })
-            .with(PhysicsBodyBuilder::from(BodyStatus::Dynamic).build())
-            .with(PhysicsColliderBuilder::from(Shape::Rectangle(15.0, 22.0, 1.0)).build())
+            .with(PhysicsBodyBuilder::<f32>::from(BodyStatus::Dynamic).build())
+            .with(
+                AutoCollider::<f32>::rectangle()
+                    .with_collider(|builder| builder.material_named("player").layer("player")),
+            )
             .with(Transform::from(Vector3::new(25.0, 50.0, 0.0)))
             .build();
 
@@ -141,13 +145,14 @@ impl<'a, 'b> GameState<'a, 'b> {
         world
             .create_entity()
             .with(
-                PhysicsColliderBuilder::from(Shape::Rectangle(10.0, 5.0, 1.0))
+                PhysicsColliderBuilder::<f32>::from(Shape::Rectangle(10.0, 5.0, 1.0))
                     .offset_from_parent(Isometry3::translation(7.5, 0.0, 0.0).into())
+                    .layer("player")
                     .sensor(true)
                     .build(),
             )
             .with(Parent { entity: player })
-            .with(Transform::from(Vector3::new(25.0, 50.0, 0.0)))
+            .with(Transform::default())
             .build();
 
         // create the player Resource
@@ -166,10 +171,12 @@ impl<'a, 'b> GameState<'a, 'b> {
                 sprite_sheet: self.objects_handle.clone(),
                 sprite_number: 0,
             })
-            .with(PhysicsBodyBuilder::from(BodyStatus::Static).build())
+            .with(PhysicsBodyBuilder::<f32>::from(BodyStatus::Static).build())
             .with(
-                PhysicsColliderBuilder::from(Shape::Rectangle(15.0, 12.0, 1.0))
+                PhysicsColliderBuilder::<f32>::from(Shape::Rectangle(15.0, 12.0, 1.0))
                     .offset_from_parent(Isometry3::translation(0.0, -4.0, 0.0).into())
+                    .material_named("terrain")
+                    .layer("terrain")
                     .build(),
             )
             //.with(Transform::from(Vector3::new(75.0, 50.0, 0.0)))
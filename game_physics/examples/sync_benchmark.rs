@@ -0,0 +1,126 @@
+//! Spawns and despawns 10k `PhysicsBody`s with `PhysicsCollider`s and reports
+//! the time spent synchronising them with the `PhysicsWorld`.
+//!
+//! ```bash
+//! $ cargo run --release --example sync_benchmark
+//! ```
+//!
+//! The example only uses APIs that predate the batched synchronisation and the
+//! generic float type, so `sync_benchmark.sh` can run it against the baseline
+//! pipeline of six add/update/remove systems as well and reports both results.
+//! The baseline is built with `--cfg sync_baseline`, which leaves out the
+//! `PhysicsStats` it does not have yet:
+//!
+//! ```bash
+//! $ ./examples/sync_benchmark.sh [<baseline ref>]
+//! ```
+
+use std::time::{Duration, Instant};
+
+use amethyst::{
+    core::{bundle::SystemBundle, math::Vector3, Transform},
+    ecs::{Builder, Dispatcher, DispatcherBuilder, World},
+};
+
+use game_physics::{
+    body::BodyStatus,
+    PhysicsBodyBuilder,
+    PhysicsBundle,
+    PhysicsColliderBuilder,
+    Shape,
+};
+
+#[cfg(not(sync_baseline))]
+use game_physics::PhysicsStats;
+
+const BODIES: usize = 10_000;
+const ROW_LENGTH: usize = 100;
+
+fn main() -> amethyst::Result<()> {
+    let mut world = World::new();
+    let mut dispatcher_builder = DispatcherBuilder::new();
+    // the type annotations pick the default float type, as the baseline
+    // types are not generic yet
+    let bundle: PhysicsBundle = PhysicsBundle::default();
+    bundle.build(&mut dispatcher_builder)?;
+    let mut dispatcher = dispatcher_builder.build();
+    dispatcher.setup(&mut world.res);
+
+    // spawn the bodies on a grid, so none of them are in contact
+    for i in 0..BODIES {
+        let body: PhysicsBodyBuilder = PhysicsBodyBuilder::from(BodyStatus::Dynamic);
+        let collider: PhysicsColliderBuilder = PhysicsColliderBuilder::from(Shape::Circle(0.5));
+        world
+            .create_entity()
+            .with(body.build())
+            .with(collider.build())
+            .with(Transform::from(Vector3::new(
+                (i % ROW_LENGTH) as f32 * 2.0,
+                (i / ROW_LENGTH) as f32 * 2.0,
+                0.0,
+            )))
+            .build();
+    }
+    let elapsed = dispatch(&mut world, &mut dispatcher);
+    report("spawn", elapsed, &world);
+
+    // a frame without any inserted or removed components
+    let elapsed = dispatch(&mut world, &mut dispatcher);
+    report("idle", elapsed, &world);
+
+    // despawn all bodies; their components are only removed on maintain
+    world.delete_all();
+    world.maintain();
+    let elapsed = dispatch(&mut world, &mut dispatcher);
+    report("despawn", elapsed, &world);
+
+    Ok(())
+}
+
+/// Runs the `Dispatcher` once and returns the total time spent.
+fn dispatch(world: &mut World, dispatcher: &mut Dispatcher) -> Duration {
+    let start = Instant::now();
+    dispatcher.dispatch(&world.res);
+    let elapsed = start.elapsed();
+    world.maintain();
+
+    elapsed
+}
+
+fn report(label: &str, elapsed: Duration, world: &World) {
+    println!("{} ({} bodies):", label, BODIES);
+    println!("  frame:            {:?}", elapsed);
+    #[cfg(not(sync_baseline))]
+    report_stats(&world.read_resource::<PhysicsStats>());
+    #[cfg(sync_baseline)]
+    let _ = world;
+}
+
+#[cfg(not(sync_baseline))]
+fn report_stats(stats: &PhysicsStats) {
+    println!(
+        "  add bodies:       {:?} ({} events)",
+        stats.add_rigid_bodies.time, stats.add_rigid_bodies.events
+    );
+    println!(
+        "  update bodies:    {:?} ({} events)",
+        stats.update_rigid_bodies.time, stats.update_rigid_bodies.events
+    );
+    println!(
+        "  remove bodies:    {:?} ({} events)",
+        stats.remove_rigid_bodies.time, stats.remove_rigid_bodies.events
+    );
+    println!(
+        "  add colliders:    {:?} ({} events)",
+        stats.add_colliders.time, stats.add_colliders.events
+    );
+    println!(
+        "  update colliders: {:?} ({} events)",
+        stats.update_colliders.time, stats.update_colliders.events
+    );
+    println!(
+        "  remove colliders: {:?} ({} events)",
+        stats.remove_colliders.time, stats.remove_colliders.events
+    );
+    println!("  step:             {:?}", stats.step_time);
+}
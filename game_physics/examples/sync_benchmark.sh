@@ -0,0 +1,29 @@
+#!/bin/sh
+# Runs the sync_benchmark example twice: against the baseline pipeline of six
+# add/update/remove systems and against the batched SyncBodiesSystem and
+# SyncCollidersSystem. The baseline is checked out into a temporary git
+# worktree; it defaults to the root commit of the repository, another ref can
+# be passed as the first argument:
+#
+#     $ ./examples/sync_benchmark.sh [<baseline ref>]
+set -e
+
+root=$(git rev-parse --show-toplevel)
+baseline=${1:-$(git -C "$root" rev-list --max-parents=0 HEAD)}
+worktree=$(mktemp -d)
+trap 'git -C "$root" worktree remove --force "$worktree"' EXIT
+
+git -C "$root" worktree add --detach "$worktree" "$baseline" > /dev/null 2>&1
+mkdir -p "$worktree/game_physics/examples"
+cp "$root/game_physics/examples/sync_benchmark.rs" "$worktree/game_physics/examples/"
+# the baseline does not track its Cargo.lock, so the same dependencies are used
+[ -f "$worktree/Cargo.lock" ] || cp "$root/Cargo.lock" "$worktree/"
+
+echo "=== baseline: six add/update/remove systems ($baseline) ==="
+(cd "$worktree/game_physics" &&
+    CARGO_TARGET_DIR="$root/target/sync_baseline" RUSTFLAGS="--cfg sync_baseline" \
+    cargo run --quiet --release --example sync_benchmark)
+
+echo
+echo "=== batched: SyncBodiesSystem and SyncCollidersSystem ==="
+(cd "$root/game_physics" && cargo run --quiet --release --example sync_benchmark)
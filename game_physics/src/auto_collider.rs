@@ -0,0 +1,211 @@
+use std::path::PathBuf;
+
+use amethyst::{
+    ecs::{Component, DenseVecStorage},
+    renderer::sprite::Sprite,
+};
+use image::RgbaImage;
+
+use crate::{
+    collider::{PhysicsCollider, PhysicsColliderBuilder, Shape},
+    math::{self, RealField},
+    Point,
+};
+
+/// The `AutoShape` defines how the `Shape` of an `AutoCollider` is derived
+/// from its sprite.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AutoShape {
+    /// A `Shape::Rectangle` with the width and height of the sprite.
+    Rectangle,
+    /// A `Shape::ConvexHull` around all pixels of the sprite with an alpha
+    /// value above `alpha_threshold`. The pixel data is read from the image
+    /// file at `texture_path`, as loaded textures only live on the GPU.
+    ConvexHull {
+        texture_path: PathBuf,
+        alpha_threshold: u8,
+    },
+}
+
+/// The `AutoCollider` `Component` derives a `PhysicsCollider` from the
+/// `SpriteRender` of the same `Entity` once its `SpriteSheet` is loaded. The
+/// `PhysicsCollider` is built with the default settings of a
+/// `PhysicsColliderBuilder`, which can be customised via `with_collider(..)`,
+/// the derived `Shape` and the sprite offsets applied to its
+/// `offset_from_parent`; the `AutoCollider` is removed afterwards. As with all
+/// `PhysicsCollider`s, the scale of the `Transform` is applied to the `Shape`.
+///
+/// For more information on how the `PhysicsCollider`s are derived, see the
+/// following `System`:
+/// - `systems::auto_colliders::AutoCollidersSystem`
+///
+/// # Example
+///
+/// ```rust
+/// use game_physics::AutoCollider;
+///
+/// let auto_collider = AutoCollider::<f32>::rectangle()
+///     .with_collider(|builder| builder.layer("player"))
+///     .with_depth(2.0);
+/// ```
+#[derive(Clone)]
+pub struct AutoCollider<N: RealField = f32> {
+    pub shape: AutoShape,
+    /// The depth of the derived `Shape` along the z axis; ignored in 2D.
+    pub depth: N,
+    pub(crate) physics_collider: PhysicsCollider<N>,
+}
+
+impl<N: RealField> Component for AutoCollider<N> {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl<N: RealField> AutoCollider<N> {
+    /// Creates a new `AutoCollider` with the given `AutoShape` and the default
+    /// `PhysicsCollider` settings.
+    pub fn new(shape: AutoShape) -> Self {
+        Self {
+            shape,
+            depth: N::one(),
+            physics_collider: Self::collider_builder().build(),
+        }
+    }
+
+    /// Creates a new `AutoCollider` deriving a `Shape::Rectangle`.
+    pub fn rectangle() -> Self {
+        Self::new(AutoShape::Rectangle)
+    }
+
+    /// Creates a new `AutoCollider` deriving a `Shape::ConvexHull` from the
+    /// alpha channel of the image file at `texture_path`. The image file is
+    /// read synchronously by the `AutoCollidersSystem`, which blocks the first
+    /// frame deriving a hull from it.
+    pub fn convex_hull<P: Into<PathBuf>>(texture_path: P, alpha_threshold: u8) -> Self {
+        Self::new(AutoShape::ConvexHull {
+            texture_path: texture_path.into(),
+            alpha_threshold,
+        })
+    }
+
+    /// Sets the settings of the derived `PhysicsCollider`. The given closure
+    /// receives a `PhysicsColliderBuilder` with the default settings; the
+    /// `Shape` it was created from is replaced by the derived one.
+    pub fn with_collider<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(PhysicsColliderBuilder<N>) -> PhysicsColliderBuilder<N>,
+    {
+        self.physics_collider = f(Self::collider_builder()).build();
+        self
+    }
+
+    /// Sets the depth of the derived `Shape`.
+    pub fn with_depth(mut self, depth: N) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Returns the `PhysicsColliderBuilder` the derived `PhysicsCollider` is
+    /// built from; its `Shape` is only a placeholder.
+    fn collider_builder() -> PhysicsColliderBuilder<N> {
+        PhysicsColliderBuilder::from(Shape::Rectangle(N::one(), N::one(), N::one()))
+    }
+
+    /// Returns the `Shape::Rectangle` matching the size of the given `Sprite`.
+    pub(crate) fn rectangle_shape(&self, sprite: &Sprite) -> Shape<N> {
+        Shape::Rectangle(
+            math::convert(f64::from(sprite.width)),
+            math::convert(f64::from(sprite.height)),
+            self.depth,
+        )
+    }
+
+    /// Returns the `Shape::ConvexHull` around the opaque pixels of the given
+    /// `Sprite` within the image, centred on the `Sprite`. Returns `None` if
+    /// there are no opaque pixels.
+    pub(crate) fn convex_hull_shape(
+        &self,
+        sprite: &Sprite,
+        image: &RgbaImage,
+        alpha_threshold: u8,
+    ) -> Option<Shape<N>> {
+        let (image_width, image_height) = (image.width() as f32, image.height() as f32);
+        let coords = &sprite.tex_coords;
+
+        // texture coordinates may be flipped along the y axis
+        let left = (coords.left.min(coords.right) * image_width).round() as u32;
+        let right = (coords.left.max(coords.right) * image_width).round() as u32;
+        let top = if coords.top <= coords.bottom {
+            coords.top * image_height
+        } else {
+            (1.0 - coords.top) * image_height
+        }
+        .round() as u32;
+        let bottom = top + ((coords.top - coords.bottom).abs() * image_height).round() as u32;
+
+        let (right, bottom) = (right.min(image.width()), bottom.min(image.height()));
+        if right <= left || bottom <= top {
+            return None;
+        }
+
+        // scale pixels to the sprite size and centre them on the sprite; the image
+        // rows grow downwards while the y axis points upwards
+        let (scale_x, scale_y) = (
+            sprite.width / (right - left) as f32,
+            sprite.height / (bottom - top) as f32,
+        );
+        let (half_width, half_height) = (sprite.width / 2.0, sprite.height / 2.0);
+
+        // the convex hull only depends on the outermost opaque pixels of each row
+        let mut outline = Vec::new();
+        for y in top..bottom {
+            let mut opaque =
+                (left..right).filter(|x| image.get_pixel(*x, y).data[3] > alpha_threshold);
+            let first = match opaque.next() {
+                Some(first) => first,
+                None => continue,
+            };
+            let last = opaque.last().unwrap_or(first);
+
+            for x in &[first - left, last - left + 1] {
+                for row in &[y - top, y - top + 1] {
+                    outline.push((
+                        *x as f32 * scale_x - half_width,
+                        half_height - *row as f32 * scale_y,
+                    ));
+                }
+            }
+        }
+
+        if outline.is_empty() {
+            return None;
+        }
+
+        Some(Shape::ConvexHull(self.hull_points(&outline)))
+    }
+
+    /// Converts the outline into points of the `PhysicsWorld` dimension; in 3D
+    /// the outline is extruded by the depth.
+    fn hull_points(&self, outline: &[(f32, f32)]) -> Vec<Point<N>> {
+        #[cfg(feature = "dim2")]
+        {
+            outline
+                .iter()
+                .map(|(x, y)| {
+                    Point::new(math::convert(f64::from(*x)), math::convert(f64::from(*y)))
+                })
+                .collect()
+        }
+        #[cfg(feature = "dim3")]
+        {
+            let half_depth = self.depth / math::convert(2.0);
+            outline
+                .iter()
+                .flat_map(|(x, y)| {
+                    let (x, y): (N, N) =
+                        (math::convert(f64::from(*x)), math::convert(f64::from(*y)));
+                    vec![Point::new(x, y, -half_depth), Point::new(x, y, half_depth)]
+                })
+                .collect()
+        }
+    }
+}
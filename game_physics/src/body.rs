@@ -4,7 +4,12 @@ use amethyst::ecs::{world::Index, Component, DenseVecStorage, FlaggedStorage};
 use nphysics::object::BodyHandle;
 pub use nphysics::object::BodyStatus;
 
-use crate::math::{Matrix3, Point3, Vector3};
+use crate::{
+    math::{self, RealField},
+    AngularInertia,
+    Point,
+    Vector,
+};
 
 /// The `HashMap` of `Index` to physics `BodyHandle` mappings. This is used for
 /// the mapping of Amethyst `Entity`s based on their unique `Index` to
@@ -16,24 +21,28 @@ pub type PhysicsBodyHandles = HashMap<Index, BodyHandle>;
 /// between both worlds.
 ///
 /// For more information on how the synchronisation is handled, see the
-/// following `System`s:
-/// - `systems::body::add_rigid_bodies::AddRigidBodiesSystem`
-/// - `systems::body::update_rigid_bodies::UpdateRigidBodiesSystem`
-/// - `systems::body::remove_rigid_bodies::RemoveRigidBodiesSystem`
+/// following `System`:
+/// - `systems::sync_bodies::SyncBodiesSystem`
 ///
-/// These `System`s work based on the `PhysicsBody` `Component`s.
+/// This `System` works based on the `PhysicsBody` `Component`s. Bodies with
+/// `mass_from_colliders` enabled are additionally handled by the
+/// `systems::mass_properties::MassPropertiesSystem`, bodies with continuous
+/// collision detection by the `systems::ccd::CcdSystem`.
 #[derive(Clone, Copy, Debug)]
-pub struct PhysicsBody {
+pub struct PhysicsBody<N: RealField = f32> {
     pub(crate) handle: Option<BodyHandle>,
     pub gravity_enabled: bool,
+    pub gravity_scale: N,
     pub body_status: BodyStatus,
-    pub velocity: Vector3<f32>,
-    pub angular_inertia: Matrix3<f32>,
-    pub mass: f32,
-    pub local_center_of_mass: Point3<f32>,
+    pub velocity: Vector<N>,
+    pub angular_inertia: AngularInertia<N>,
+    pub mass: N,
+    pub local_center_of_mass: Point<N>,
+    pub mass_from_colliders: bool,
+    pub ccd_motion_threshold: Option<N>,
 }
 
-impl Component for PhysicsBody {
+impl<N: RealField> Component for PhysicsBody<N> {
     type Storage = FlaggedStorage<Self, DenseVecStorage<Self>>;
 }
 
@@ -44,86 +53,122 @@ impl Component for PhysicsBody {
 /// # Example
 ///
 /// ```rust
-/// use game_physics::{
-///     body::BodyStatus,
-///     math::{Matrix3, Point3, Vector3},
-///     PhysicsBodyBuilder,
-/// };
+/// use game_physics::{body::BodyStatus, math, PhysicsBodyBuilder, Point, Vector};
 ///
-/// let physics_body = PhysicsBodyBuilder::from(BodyStatus::Dynamic)
+/// let physics_body = PhysicsBodyBuilder::<f32>::from(BodyStatus::Dynamic)
 ///     .gravity_enabled(true)
-///     .velocity(Vector3::new(1.0, 1.0, 1.0))
-///     .angular_inertia(Matrix3::from_diagonal_element(3.0))
+///     .gravity_scale(0.5)
+///     .velocity(Vector::repeat(1.0))
+///     .angular_inertia(math::zero())
 ///     .mass(1.3)
-///     .local_center_of_mass(Point3::new(0.0, 0.0, 0.0))
+///     .local_center_of_mass(Point::origin())
+///     .mass_from_colliders(false)
+///     .ccd(0.5)
 ///     .build();
 /// ```
-pub struct PhysicsBodyBuilder {
+pub struct PhysicsBodyBuilder<N: RealField = f32> {
     gravity_enabled: bool,
+    gravity_scale: N,
     body_status: BodyStatus,
-    velocity: Vector3<f32>,
-    angular_inertia: Matrix3<f32>,
-    mass: f32,
-    local_center_of_mass: Point3<f32>,
+    velocity: Vector<N>,
+    angular_inertia: AngularInertia<N>,
+    mass: N,
+    local_center_of_mass: Point<N>,
+    mass_from_colliders: bool,
+    ccd_motion_threshold: Option<N>,
 }
 
-impl From<BodyStatus> for PhysicsBodyBuilder {
+impl<N: RealField> From<BodyStatus> for PhysicsBodyBuilder<N> {
     /// Creates a new `PhysicsBodyBuilder` from the given `BodyStatus`. This
     /// also populates the `PhysicsBody` with sane defaults.
     fn from(body_status: BodyStatus) -> Self {
         Self {
             gravity_enabled: false,
+            gravity_scale: N::one(),
             body_status,
-            velocity: Vector3::new(0.0, 0.0, 0.0),
-            angular_inertia: Matrix3::zeros(),
-            mass: 1.2,
-            local_center_of_mass: Point3::new(0.0, 0.0, 0.0),
+            velocity: Vector::zeros(),
+            angular_inertia: math::zero(),
+            mass: math::convert(1.2),
+            local_center_of_mass: Point::origin(),
+            mass_from_colliders: false,
+            ccd_motion_threshold: None,
         }
     }
 }
 
-impl PhysicsBodyBuilder {
+impl<N: RealField> PhysicsBodyBuilder<N> {
     /// Sets the `gravity_enabled` value of the `PhysicsBodyBuilder`.
     pub fn gravity_enabled(mut self, gravity_enabled: bool) -> Self {
         self.gravity_enabled = gravity_enabled;
         self
     }
 
+    /// Sets the `gravity_scale` value of the `PhysicsBodyBuilder`. The
+    /// `Gravity` (or the gravity of the `GravityZone`s the body is in) is
+    /// multiplied by this factor.
+    pub fn gravity_scale(mut self, gravity_scale: N) -> Self {
+        self.gravity_scale = gravity_scale;
+        self
+    }
+
     // Sets the `velocity` value of the `PhysicsBodyBuilder`.
-    pub fn velocity(mut self, velocity: Vector3<f32>) -> Self {
+    pub fn velocity(mut self, velocity: Vector<N>) -> Self {
         self.velocity = velocity;
         self
     }
 
     /// Sets the `angular_inertia` value of the `PhysicsBodyBuilder`.
-    pub fn angular_inertia(mut self, angular_inertia: Matrix3<f32>) -> Self {
+    pub fn angular_inertia(mut self, angular_inertia: AngularInertia<N>) -> Self {
         self.angular_inertia = angular_inertia;
         self
     }
 
     /// Sets the `mass` value of the `PhysicsBodyBuilder`.
-    pub fn mass(mut self, mass: f32) -> Self {
+    pub fn mass(mut self, mass: N) -> Self {
         self.mass = mass;
         self
     }
 
     /// Sets the `local_center_of_mass` value of the `PhysicsBodyBuilder`.
-    pub fn local_center_of_mass(mut self, local_center_of_mass: Point3<f32>) -> Self {
+    pub fn local_center_of_mass(mut self, local_center_of_mass: Point<N>) -> Self {
         self.local_center_of_mass = local_center_of_mass;
         self
     }
 
+    /// Sets the `mass_from_colliders` value of the `PhysicsBodyBuilder`. If
+    /// enabled, the `mass`, `angular_inertia` and `local_center_of_mass` of the
+    /// `RigidBody` are derived from the shapes and densities of all attached
+    /// non-sensor `PhysicsCollider`s instead of the explicit values; the
+    /// explicit values remain as fallback for bodies without colliders.
+    pub fn mass_from_colliders(mut self, mass_from_colliders: bool) -> Self {
+        self.mass_from_colliders = mass_from_colliders;
+        self
+    }
+
+    /// Enables continuous collision detection (CCD) for the
+    /// `PhysicsBodyBuilder`. Whenever the `RigidBody` would move further than
+    /// `motion_threshold` during a single step, its motion is checked for
+    /// obstacles via ray casts to prevent it from tunnelling through thin
+    /// `PhysicsCollider`s.
+    pub fn ccd(mut self, motion_threshold: N) -> Self {
+        self.ccd_motion_threshold = Some(motion_threshold);
+        self
+    }
+
     /// Builds the `PhysicsBody` from the values set in the `PhysicsBodyBuilder`
     /// instance.
-    pub fn build(self) -> PhysicsBody {
+    pub fn build(self) -> PhysicsBody<N> {
         PhysicsBody {
             handle: None,
             gravity_enabled: self.gravity_enabled,
+            gravity_scale: self.gravity_scale,
             body_status: self.body_status,
             velocity: self.velocity,
             angular_inertia: self.angular_inertia,
             mass: self.mass,
             local_center_of_mass: self.local_center_of_mass,
+            mass_from_colliders: self.mass_from_colliders,
+            ccd_motion_threshold: self.ccd_motion_threshold,
         }
     }
 }
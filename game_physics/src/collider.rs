@@ -1,12 +1,25 @@
-use std::{collections::HashMap, f32::consts::PI, fmt};
+use std::{collections::HashMap, fmt};
 
 use amethyst::ecs::{world::Index, Component, DenseVecStorage, FlaggedStorage};
+#[cfg(feature = "dim3")]
+use ncollide::shape::ConvexHull;
+#[cfg(feature = "dim2")]
+use ncollide::shape::ConvexPolygon;
 use ncollide::shape::{Ball, Cuboid, ShapeHandle};
 pub use ncollide::world::CollisionGroups;
 pub use nphysics::material;
-use nphysics::object::ColliderHandle;
+use nphysics::{
+    math::Translation,
+    object::{BodyHandle, ColliderHandle},
+};
+use serde::{Deserialize, Serialize};
 
-use crate::math::{Isometry3, Vector3};
+use crate::{
+    math::{self, RealField},
+    Isometry,
+    Point,
+    Vector,
+};
 
 use self::material::{BasicMaterial, MaterialHandle};
 
@@ -18,24 +31,81 @@ pub type PhysicsColliderHandles = HashMap<Index, ColliderHandle>;
 /// `Shape` serves as an abstraction over nphysics `ShapeHandle`s and makes it
 /// easier to configure and define said `ShapeHandle`s for the user without
 /// having to know the underlying nphysics API.
-#[derive(Clone, Copy, Debug)]
-pub enum Shape {
-    Circle(f32),
-    Rectangle(f32, f32, f32),
+///
+/// With the `dim2` feature enabled, `Shape::Circle` maps to a 2D ball and
+/// `Shape::Rectangle` to a 2D cuboid; the depth of the rectangle is ignored.
+/// `Shape::ConvexHull` is the convex hull of the given local space points; its
+/// size is not reduced by the margin.
+///
+/// The scale of the `Transform` is applied to the `Shape` dimensions.
+/// `Shape::Rectangle` and `Shape::ConvexHull` support non-uniform scales;
+/// `Shape::Circle` only supports uniform scales and uses the largest absolute
+/// scale factor otherwise.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum Shape<N: RealField = f32> {
+    Circle(N),
+    Rectangle(N, N, N),
+    ConvexHull(Vec<Point<N>>),
 }
 
-impl Shape {
+impl<N: RealField> Shape<N> {
+    /// Returns the `Shape` with its dimensions multiplied by the given scale.
+    pub fn scaled(&self, scale: &Vector<N>) -> Self {
+        match *self {
+            Shape::Circle(radius) => Shape::Circle(radius * scale.amax()),
+            #[cfg(feature = "dim2")]
+            Shape::Rectangle(width, height, depth) => {
+                Shape::Rectangle(width * scale.x.abs(), height * scale.y.abs(), depth)
+            }
+            #[cfg(feature = "dim3")]
+            Shape::Rectangle(width, height, depth) => Shape::Rectangle(
+                width * scale.x.abs(),
+                height * scale.y.abs(),
+                depth * scale.z.abs(),
+            ),
+            Shape::ConvexHull(ref points) => Shape::ConvexHull(
+                points
+                    .iter()
+                    .map(|point| Point::from(point.coords.component_mul(scale)))
+                    .collect(),
+            ),
+        }
+    }
+
     /// Converts a `Shape` and its values into its corresponding `ShapeHandle`
     /// type. The `ShapeHandle` is used to define a `Collider` in the
     /// `PhysicsWorld`.
-    fn handle(&self, margin: f32) -> ShapeHandle<f32> {
+    fn handle(&self, margin: N) -> ShapeHandle<N> {
+        let two: N = math::convert(2.0);
         match *self {
             Shape::Circle(radius) => ShapeHandle::new(Ball::new(radius)),
-            Shape::Rectangle(width, height, depth) => ShapeHandle::new(Cuboid::new(Vector3::new(
-                width / 2.0 - margin,
-                height / 2.0 - margin,
-                depth / 2.0 - margin,
+            #[cfg(feature = "dim2")]
+            Shape::Rectangle(width, height, _) => ShapeHandle::new(Cuboid::new(Vector::new(
+                width / two - margin,
+                height / two - margin,
+            ))),
+            #[cfg(feature = "dim3")]
+            Shape::Rectangle(width, height, depth) => ShapeHandle::new(Cuboid::new(Vector::new(
+                width / two - margin,
+                height / two - margin,
+                depth / two - margin,
             ))),
+            #[cfg(feature = "dim2")]
+            Shape::ConvexHull(ref points) => match ConvexPolygon::try_from_points(points) {
+                Some(convex_polygon) => ShapeHandle::new(convex_polygon),
+                None => {
+                    warn!("Degenerate convex hull, falling back to a ball");
+                    ShapeHandle::new(Ball::new(margin))
+                }
+            },
+            #[cfg(feature = "dim3")]
+            Shape::ConvexHull(ref points) => match ConvexHull::try_from_points(points) {
+                Some(convex_hull) => ShapeHandle::new(convex_hull),
+                None => {
+                    warn!("Degenerate convex hull, falling back to a ball");
+                    ShapeHandle::new(Ball::new(margin))
+                }
+            },
         }
     }
 }
@@ -46,31 +116,35 @@ impl Shape {
 /// the physic worlds `Collider`.
 ///
 /// For more information on how the synchronisation is handled, see the
-/// following `System`s:
-/// - `systems::collider::add_colliders::AddCollidersSystem`
-/// - `systems::collider::update_colliders::UpdateCollidersSystem`
-/// - `systems::collider::remove_colliders::RemoveCollidersSystem`
+/// following `System`:
+/// - `systems::sync_colliders::SyncCollidersSystem`
 ///
-/// These `System`s work based on the `PhysicsCollider` `Component`s.
+/// This `System` works based on the `PhysicsCollider` `Component`s.
 #[derive(Clone)]
-pub struct PhysicsCollider {
+pub struct PhysicsCollider<N: RealField = f32> {
     pub(crate) handle: Option<ColliderHandle>,
-    pub shape: Shape,
-    pub offset_from_parent: Isometry3<f32>,
-    pub density: f32,
-    pub material: MaterialHandle<f32>,
-    pub margin: f32,
+    pub shape: Shape<N>,
+    pub offset_from_parent: Isometry<N>,
+    pub density: N,
+    pub material: MaterialHandle<N>,
+    pub material_name: Option<String>,
+    pub margin: N,
     pub collision_groups: CollisionGroups,
-    pub linear_prediction: f32,
-    pub angular_prediction: f32,
+    pub layer: Option<String>,
+    pub collides_with: Option<Vec<String>>,
+    pub linear_prediction: N,
+    pub angular_prediction: N,
     pub sensor: bool,
+    pub(crate) scale: Vector<N>,
+    pub(crate) position_wrt_body: Isometry<N>,
+    pub(crate) body_handle: Option<BodyHandle>,
 }
 
-impl Component for PhysicsCollider {
+impl<N: RealField> Component for PhysicsCollider<N> {
     type Storage = FlaggedStorage<Self, DenseVecStorage<Self>>;
 }
 
-impl fmt::Debug for PhysicsCollider {
+impl<N: RealField> fmt::Debug for PhysicsCollider<N> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
@@ -78,30 +152,58 @@ impl fmt::Debug for PhysicsCollider {
              handle: {:?}, \
              offset_from_parent: {:?}, \
              density: {}, \
+             material_name: {:?}, \
              margin: {}, \
              collision_group: {:?}, \
+             layer: {:?}, \
+             collides_with: {:?}, \
              linear_prediction: {}, \
              angular_prediction: {}, \
-             sensor: {} \
+             sensor: {}, \
+             scale: {:?} \
              }}",
             self.handle,
             self.offset_from_parent,
             self.density,
+            self.material_name,
             self.margin,
             self.collision_groups,
+            self.layer,
+            self.collides_with,
             self.linear_prediction,
             self.angular_prediction,
             self.sensor,
+            self.scale,
         )?;
         Ok(())
     }
 }
 
-impl PhysicsCollider {
-    /// Returns the `ShapeHandle` for `shape`, taking the `margin` into
-    /// consideration.
-    pub(crate) fn shape_handle(&self) -> ShapeHandle<f32> {
-        self.shape.handle(self.margin)
+impl<N: RealField> PhysicsCollider<N> {
+    /// Returns the `shape` scaled by the scale of the `Transform` the
+    /// `Collider` was built with.
+    pub fn scaled_shape(&self) -> Shape<N> {
+        self.shape.scaled(&self.scale)
+    }
+
+    /// Returns the `offset_from_parent` with its translation scaled by the
+    /// scale of the `Transform` the `Collider` was built with.
+    pub fn scaled_offset_from_parent(&self) -> Isometry<N> {
+        Isometry::from_parts(
+            Translation::from(
+                self.offset_from_parent
+                    .translation
+                    .vector
+                    .component_mul(&self.scale),
+            ),
+            self.offset_from_parent.rotation,
+        )
+    }
+
+    /// Returns the `ShapeHandle` for the scaled `shape`, taking the `margin`
+    /// into consideration.
+    pub(crate) fn shape_handle(&self) -> ShapeHandle<N> {
+        self.scaled_shape().handle(self.margin)
     }
 }
 
@@ -117,73 +219,89 @@ impl PhysicsCollider {
 ///         material::{BasicMaterial, MaterialHandle},
 ///         CollisionGroups,
 ///     },
-///     math::Isometry3,
+///     Isometry,
 ///     PhysicsColliderBuilder,
 ///     Shape,
 /// };
 ///
-/// let physics_collider = PhysicsColliderBuilder::from(Shape::Rectangle(10.0, 10.0, 1.0))
-///     .offset_from_parent(Isometry3::identity())
+/// let physics_collider = PhysicsColliderBuilder::<f32>::from(Shape::Rectangle(10.0, 10.0, 1.0))
+///     .offset_from_parent(Isometry::identity())
 ///     .density(1.2)
 ///     .material(MaterialHandle::new(BasicMaterial::default()))
 ///     .margin(0.02)
 ///     .collision_groups(CollisionGroups::default())
+///     .layer("player")
+///     .collides_with(&["terrain"])
 ///     .linear_prediction(0.001)
 ///     .angular_prediction(0.0)
 ///     .sensor(true)
 ///     .build();
 /// ```
-pub struct PhysicsColliderBuilder {
-    shape: Shape,
-    offset_from_parent: Isometry3<f32>,
-    density: f32,
-    material: MaterialHandle<f32>,
-    margin: f32,
+pub struct PhysicsColliderBuilder<N: RealField = f32> {
+    shape: Shape<N>,
+    offset_from_parent: Isometry<N>,
+    density: N,
+    material: MaterialHandle<N>,
+    material_name: Option<String>,
+    margin: N,
     collision_groups: CollisionGroups,
-    linear_prediction: f32,
-    angular_prediction: f32,
+    layer: Option<String>,
+    collides_with: Option<Vec<String>>,
+    linear_prediction: N,
+    angular_prediction: N,
     sensor: bool,
 }
 
-impl From<Shape> for PhysicsColliderBuilder {
+impl<N: RealField> From<Shape<N>> for PhysicsColliderBuilder<N> {
     /// Creates a new `PhysicsColliderBuilder` from the given `Shape`. This
     //  also populates the `PhysicsCollider` with sane defaults.
-    fn from(shape: Shape) -> Self {
+    fn from(shape: Shape<N>) -> Self {
         Self {
             shape,
-            offset_from_parent: Isometry3::identity(),
-            density: 1.3,
+            offset_from_parent: Isometry::identity(),
+            density: math::convert(1.3),
             material: MaterialHandle::new(BasicMaterial::default()),
-            margin: 0.2, // default was: 0.01
+            material_name: None,
+            margin: math::convert(0.2), // default was: 0.01
             collision_groups: CollisionGroups::default(),
-            linear_prediction: 0.002,
-            angular_prediction: PI / 180.0 * 5.0,
+            layer: None,
+            collides_with: None,
+            linear_prediction: math::convert(0.002),
+            angular_prediction: N::pi() / math::convert(180.0) * math::convert(5.0),
             sensor: false,
         }
     }
 }
 
-impl PhysicsColliderBuilder {
+impl<N: RealField> PhysicsColliderBuilder<N> {
     /// Sets the `offset_from_parent` value of the `PhysicsColliderBuilder`.
-    pub fn offset_from_parent(mut self, offset_from_parent: Isometry3<f32>) -> Self {
+    pub fn offset_from_parent(mut self, offset_from_parent: Isometry<N>) -> Self {
         self.offset_from_parent = offset_from_parent;
         self
     }
 
     /// Sets the `density` value of the `PhysicsColliderBuilder`.
-    pub fn density(mut self, density: f32) -> Self {
+    pub fn density(mut self, density: N) -> Self {
         self.density = density;
         self
     }
 
     /// Sets the `material` value of the `PhysicsColliderBuilder`.
-    pub fn material(mut self, material: MaterialHandle<f32>) -> Self {
+    pub fn material(mut self, material: MaterialHandle<N>) -> Self {
         self.material = material;
         self
     }
 
+    /// Sets the named material of the `PhysicsColliderBuilder`. The name is
+    /// resolved to a shared `MaterialHandle` via the `PhysicsMaterials`
+    /// `Resource` and overrides the `material` value.
+    pub fn material_named(mut self, material_name: &str) -> Self {
+        self.material_name = Some(material_name.to_string());
+        self
+    }
+
     /// Sets the `margin` value of the `PhysicsColliderBuilder`.
-    pub fn margin(mut self, margin: f32) -> Self {
+    pub fn margin(mut self, margin: N) -> Self {
         self.margin = margin;
         self
     }
@@ -194,14 +312,31 @@ impl PhysicsColliderBuilder {
         self
     }
 
+    /// Sets the named `layer` of the `PhysicsColliderBuilder`. The layer is
+    /// resolved via the `CollisionLayers` `Resource` and overrides the
+    /// `collision_groups` value.
+    pub fn layer(mut self, layer: &str) -> Self {
+        self.layer = Some(layer.to_string());
+        self
+    }
+
+    /// Sets the named layers the `PhysicsColliderBuilder` collides with,
+    /// overriding the interaction matrix of the `CollisionLayers` `Resource`.
+    /// Only has an effect in combination with `layer(..)`; note that the other
+    /// layers have to interact with this layer as well.
+    pub fn collides_with(mut self, layers: &[&str]) -> Self {
+        self.collides_with = Some(layers.iter().map(|layer| layer.to_string()).collect());
+        self
+    }
+
     /// Sets the `linear_prediction` value of the `PhysicsColliderBuilder`.
-    pub fn linear_prediction(mut self, linear_prediction: f32) -> Self {
+    pub fn linear_prediction(mut self, linear_prediction: N) -> Self {
         self.linear_prediction = linear_prediction;
         self
     }
 
     /// Sets the `angular_prediction` value of the `PhysicsColliderBuilder`.
-    pub fn angular_prediction(mut self, angular_prediction: f32) -> Self {
+    pub fn angular_prediction(mut self, angular_prediction: N) -> Self {
         self.angular_prediction = angular_prediction;
         self
     }
@@ -214,18 +349,24 @@ impl PhysicsColliderBuilder {
 
     /// Builds the `PhysicsCollider` from the values set in the
     /// `PhysicsColliderBuilder` instance.
-    pub fn build(self) -> PhysicsCollider {
+    pub fn build(self) -> PhysicsCollider<N> {
         PhysicsCollider {
             handle: None,
             shape: self.shape,
             offset_from_parent: self.offset_from_parent,
             density: self.density,
             material: self.material,
+            material_name: self.material_name,
             margin: self.margin,
             collision_groups: self.collision_groups,
+            layer: self.layer,
+            collides_with: self.collides_with,
             linear_prediction: self.linear_prediction,
             angular_prediction: self.angular_prediction,
             sensor: self.sensor,
+            scale: Vector::repeat(N::one()),
+            position_wrt_body: Isometry::identity(),
+            body_handle: None,
         }
     }
 }
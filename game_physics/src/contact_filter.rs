@@ -0,0 +1,231 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use amethyst::ecs::{world::Index, Component, DenseVecStorage, Entity, FlaggedStorage};
+use ncollide::{
+    broad_phase::BroadPhasePairFilter,
+    query,
+    world::{CollisionObject, GeometricQueryType},
+};
+use nphysics::object::{ColliderData, ColliderHandle};
+
+use crate::{
+    math::{self, RealField},
+    Vector,
+};
+
+/// The `OneWayPlatform` `Component` turns the `PhysicsCollider` of the same
+/// `Entity` into a platform that only collides with `PhysicsCollider`s on the
+/// side its `normal` points to. The side is checked anew before every step
+/// while a moving `PhysicsCollider` is close to the platform:
+/// `PhysicsCollider`s approaching from any other side pass through the
+/// platform and are only blocked once the contact normal points along the
+/// platform `normal`. The `normal` is given in the local space of the
+/// `PhysicsCollider`.
+///
+/// For more information on how the contacts are filtered, see the following
+/// `System`:
+/// - `systems::contact_filters::ContactFiltersSystem`
+///
+/// # Example
+///
+/// ```rust
+/// use game_physics::{OneWayPlatform, Vector};
+///
+/// let one_way_platform = OneWayPlatform::<f32>::new(Vector::y());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OneWayPlatform<N: RealField = f32> {
+    pub normal: Vector<N>,
+}
+
+impl<N: RealField> Component for OneWayPlatform<N> {
+    type Storage = FlaggedStorage<Self, DenseVecStorage<Self>>;
+}
+
+impl<N: RealField> Default for OneWayPlatform<N> {
+    fn default() -> Self {
+        Self::new(Vector::y())
+    }
+}
+
+impl<N: RealField> OneWayPlatform<N> {
+    /// Creates a new `OneWayPlatform` with the given (normalised) `normal`.
+    pub fn new(normal: Vector<N>) -> Self {
+        Self {
+            normal: normal.normalize(),
+        }
+    }
+}
+
+/// The `ContactFilterPair` describes a pair of `PhysicsCollider`s coming into
+/// contact.
+#[derive(Clone, Copy, Debug)]
+pub struct ContactFilterPair<N: RealField = f32> {
+    pub entity1: Entity,
+    pub entity2: Entity,
+    /// The predicted contact normal, pointing from the `PhysicsCollider` of
+    /// `entity1` towards the one of `entity2`, if it could be computed.
+    pub normal: Option<Vector<N>>,
+}
+
+/// The `ContactFilter` decides whether the contacts between a pair of
+/// `PhysicsCollider`s should be generated. It is evaluated for every pair of
+/// `PhysicsCollider`s once their bounding boxes start to overlap and for all
+/// overlapping pairs whenever `reevaluate` returns `true`, which it does
+/// before every step by default; e.g. a character standing on a platform drops
+/// through it as soon as the pair is rejected. Sensor `PhysicsCollider`s are
+/// never filtered.
+///
+/// nphysics 0.11 offers no hook into the contact manifolds before the solver
+/// runs. The decision is therefore made for the whole pair via the broad
+/// phase, based on the contact normal predicted from the shapes of both
+/// `PhysicsCollider`s, rather than for individual contact points.
+///
+/// The `ContactFilter` is implemented for all matching closures.
+pub trait ContactFilter<N: RealField>: Send + Sync {
+    /// Returns `false` if the contacts of the given pair should be ignored.
+    fn accepts(&self, pair: &ContactFilterPair<N>) -> bool;
+
+    /// Returns `true` if all overlapping pairs have to be evaluated anew
+    /// during the upcoming step, e.g. because the state the decisions depend
+    /// on changed. Re-evaluating all pairs is expensive, so filters whose
+    /// decisions rarely change should only return `true` when they do.
+    fn reevaluate(&self) -> bool {
+        true
+    }
+}
+
+impl<N, F> ContactFilter<N> for F
+where
+    N: RealField,
+    F: Fn(&ContactFilterPair<N>) -> bool + Send + Sync,
+{
+    fn accepts(&self, pair: &ContactFilterPair<N>) -> bool {
+        self(pair)
+    }
+}
+
+/// The state shared between the `ContactFiltersSystem` and the broad phase of
+/// the `PhysicsWorld`: the `Entity` of every `Collider` and the normals of all
+/// `OneWayPlatform`s by the `Index` of their `Entity`.
+pub(crate) struct ContactFilterState<N: RealField> {
+    pub(crate) entities: HashMap<ColliderHandle, Entity>,
+    pub(crate) one_way_platforms: HashMap<Index, Vector<N>>,
+    pub(crate) filter: Option<Arc<dyn ContactFilter<N>>>,
+}
+
+/// The `ContactFilterPairs` are the ncollide `BroadPhasePairFilter` applying
+/// `OneWayPlatform`s and the user provided `ContactFilter`.
+pub(crate) struct ContactFilterPairs<N: RealField> {
+    pub(crate) state: Arc<Mutex<ContactFilterState<N>>>,
+}
+
+impl<N: RealField> ContactFilterPairs<N> {
+    /// Creates new `ContactFilterPairs` with the given optional
+    /// `ContactFilter`.
+    pub(crate) fn new(filter: Option<Arc<dyn ContactFilter<N>>>) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(ContactFilterState {
+                entities: HashMap::new(),
+                one_way_platforms: HashMap::new(),
+                filter,
+            })),
+        }
+    }
+}
+
+impl<N: RealField> Clone for ContactFilterPairs<N> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<N: RealField> BroadPhasePairFilter<N, ColliderData<N>> for ContactFilterPairs<N> {
+    fn is_pair_valid(
+        &self,
+        b1: &CollisionObject<N, ColliderData<N>>,
+        b2: &CollisionObject<N, ColliderData<N>>,
+    ) -> bool {
+        // sensors only generate proximity events and are never filtered
+        if is_sensor(b1) || is_sensor(b2) {
+            return true;
+        }
+
+        let state = self.state.lock().unwrap();
+        let entity1 = state.entities.get(&b1.handle()).cloned();
+        let entity2 = state.entities.get(&b2.handle()).cloned();
+        let platform = |entity: Option<Entity>| {
+            entity.and_then(|entity| state.one_way_platforms.get(&entity.id()).cloned())
+        };
+        let platform1 = platform(entity1);
+        let platform2 = platform(entity2);
+        if platform1.is_none() && platform2.is_none() && state.filter.is_none() {
+            return true;
+        }
+
+        let normal = contact_normal(b1, b2);
+
+        // the other Collider has to be on the side the platform normal points to
+        let mut valid = true;
+        if let Some(platform_normal) = platform1 {
+            let platform_normal = b1.position().rotation * platform_normal;
+            valid &= normal.map_or(true, |normal| {
+                normal.dot(&platform_normal) > N::default_epsilon()
+            });
+        }
+        if let Some(platform_normal) = platform2 {
+            let platform_normal = b2.position().rotation * platform_normal;
+            valid &= normal.map_or(true, |normal| {
+                (-normal).dot(&platform_normal) > N::default_epsilon()
+            });
+        }
+
+        if valid {
+            if let Some(filter) = &state.filter {
+                if let (Some(entity1), Some(entity2)) = (entity1, entity2) {
+                    valid = filter.accepts(&ContactFilterPair {
+                        entity1,
+                        entity2,
+                        normal,
+                    });
+                }
+            }
+        }
+
+        valid
+    }
+}
+
+/// Checks whether the given `CollisionObject` is a sensor.
+fn is_sensor<N: RealField>(collision_object: &CollisionObject<N, ColliderData<N>>) -> bool {
+    match collision_object.query_type() {
+        GeometricQueryType::Proximity(_) => true,
+        _ => false,
+    }
+}
+
+/// Predicts the contact normal between two `CollisionObject`s with
+/// overlapping bounding boxes, pointing from `b1` towards `b2`.
+fn contact_normal<N: RealField>(
+    b1: &CollisionObject<N, ColliderData<N>>,
+    b2: &CollisionObject<N, ColliderData<N>>,
+) -> Option<Vector<N>> {
+    // the bounding boxes overlap, so the shapes are at most this far apart
+    let prediction = (b1.shape().bounding_sphere(b1.position()).radius()
+        + b2.shape().bounding_sphere(b2.position()).radius())
+        * math::convert(2.0);
+
+    query::contact(
+        b1.position(),
+        b1.shape().as_ref(),
+        b2.position(),
+        b2.shape().as_ref(),
+        prediction,
+    )
+    .map(|contact| contact.normal.into_inner())
+}
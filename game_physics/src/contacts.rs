@@ -0,0 +1,270 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use amethyst::ecs::{Component, DenseVecStorage, Entity};
+use nphysics::{
+    detection::ColliderContactManifold,
+    object::{BodySet, ColliderHandle},
+    solver::{ConstraintSet, ContactModel, IntegrationParameters, SignoriniCoulombPyramidModel},
+};
+
+use crate::{
+    math::{DVector, RealField},
+    Point,
+    Vector,
+};
+
+/// The `Contacts` `Component` lists all `Entity`s the `PhysicsCollider` of the
+/// same `Entity` currently touches, including the contact points. The
+/// `Component` is opt-in: it is only filled for `Entity`s that were given a
+/// `Contacts` `Component` and is updated after every `PhysicsWorld` step.
+///
+/// Sensor `PhysicsCollider`s never generate contacts; see `TriggerVolume` for
+/// tracking overlaps of sensors.
+///
+/// For more information on how the contacts are collected, see the following
+/// `System`:
+/// - `systems::contacts::ContactsSystem`
+#[derive(Clone, Debug)]
+pub struct Contacts<N: RealField = f32> {
+    pub(crate) contacts: Vec<EntityContact<N>>,
+}
+
+impl<N: RealField> Component for Contacts<N> {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl<N: RealField> Default for Contacts<N> {
+    fn default() -> Self {
+        Self {
+            contacts: Vec::new(),
+        }
+    }
+}
+
+impl<N: RealField> Contacts<N> {
+    /// Returns an `Iterator` over all current contacts.
+    pub fn iter(&self) -> impl Iterator<Item = &EntityContact<N>> {
+        self.contacts.iter()
+    }
+
+    /// Returns the contact with the given `Entity`, if both are touching.
+    pub fn get(&self, entity: Entity) -> Option<&EntityContact<N>> {
+        self.contacts
+            .iter()
+            .find(|contact| contact.entity == entity)
+    }
+
+    /// Checks whether the `Entity` is currently touching the given `Entity`.
+    pub fn is_touching(&self, entity: Entity) -> bool {
+        self.get(entity).is_some()
+    }
+
+    /// Checks whether the `Entity` is currently touching anything.
+    pub fn is_empty(&self) -> bool {
+        self.contacts.is_empty()
+    }
+}
+
+/// The `EntityContact` describes the contact with another `Entity`.
+#[derive(Clone, Debug)]
+pub struct EntityContact<N: RealField = f32> {
+    /// The touched `Entity`.
+    pub entity: Entity,
+    /// The individual contact points of the contact manifold.
+    pub points: Vec<ContactPoint<N>>,
+}
+
+/// A single point of contact between two `Entity`s.
+#[derive(Clone, Copy, Debug)]
+pub struct ContactPoint<N: RealField = f32> {
+    /// The world space contact point on the `Collider` of this `Entity`.
+    pub point: Point<N>,
+    /// The world space contact point on the `Collider` of the touched
+    /// `Entity`.
+    pub other_point: Point<N>,
+    /// The contact normal, pointing from this `Entity` towards the touched
+    /// `Entity`.
+    pub normal: Vector<N>,
+    /// The penetration depth; negative values indicate a separation within
+    /// the `Collider` margins.
+    pub depth: N,
+    /// The impulse the constraint solver applied along the contact normal
+    /// during the last step. It is zero if the contact was not solved, e.g.
+    /// because both `RigidBody`s are sleeping or the `Collider`s are still
+    /// separated.
+    pub impulse: N,
+}
+
+/// The `ContactImpulses` hold the normal impulses the constraint solver
+/// applied during the last step, keyed by the pair of `Collider`s and listed
+/// in the order of the contacts of their contact manifold. They are recorded
+/// by the `ImpulseRecorder` the `PhysicsStepperSystem` installs as the
+/// `ContactModel` of the `PhysicsWorld` and cleared before every step.
+pub(crate) struct ContactImpulses<N: RealField> {
+    impulses: Arc<Mutex<HashMap<(ColliderHandle, ColliderHandle), Vec<N>>>>,
+}
+
+impl<N: RealField> Clone for ContactImpulses<N> {
+    fn clone(&self) -> Self {
+        Self {
+            impulses: self.impulses.clone(),
+        }
+    }
+}
+
+impl<N: RealField> Default for ContactImpulses<N> {
+    fn default() -> Self {
+        Self {
+            impulses: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl<N: RealField> ContactImpulses<N> {
+    /// Removes the impulses of the previous step.
+    pub(crate) fn clear(&self) {
+        self.impulses.lock().unwrap().clear();
+    }
+
+    /// Returns the impulses of the contact manifold between the given
+    /// `Collider`s, in the order of its contacts, or `None` if it was not
+    /// solved during the last step.
+    pub(crate) fn get(&self, handle1: ColliderHandle, handle2: ColliderHandle) -> Option<Vec<N>> {
+        let impulses = self.impulses.lock().unwrap();
+        impulses
+            .get(&(handle1, handle2))
+            .or_else(|| impulses.get(&(handle2, handle1)))
+            .cloned()
+    }
+}
+
+/// The velocity constraint generated for a single contact: its position in
+/// either the ground or the non-ground unilateral constraints of the
+/// `ConstraintSet`.
+#[derive(Clone, Copy)]
+enum ContactConstraint {
+    Ground(usize),
+    Free(usize),
+    Inactive,
+}
+
+/// The `ImpulseRecorder` is a `ContactModel` wrapping the default
+/// `SignoriniCoulombPyramidModel` of nphysics. It passes all calls on and
+/// additionally records the impulse of every normal contact constraint once
+/// the solver is done, which nphysics keeps private otherwise.
+///
+/// The inner model creates exactly one unilateral constraint per active
+/// contact, in the order of the contact manifolds: a ground constraint if one
+/// of the two bodies has no degrees of freedom, e.g. a static or kinematic
+/// body, and a regular one otherwise. The recorder resolves the constraints
+/// of each contact the same way; if the number of constraints ever differs
+/// from the expected one, no impulses are recorded for the step.
+pub(crate) struct ImpulseRecorder<N: RealField> {
+    model: SignoriniCoulombPyramidModel<N>,
+    impulses: ContactImpulses<N>,
+    pending: Vec<((ColliderHandle, ColliderHandle), Vec<ContactConstraint>)>,
+}
+
+impl<N: RealField> ImpulseRecorder<N> {
+    /// Creates a new `ImpulseRecorder` recording into the given
+    /// `ContactImpulses`.
+    pub(crate) fn new(impulses: ContactImpulses<N>) -> Self {
+        Self {
+            model: SignoriniCoulombPyramidModel::new(),
+            impulses,
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl<N: RealField> ContactModel<N> for ImpulseRecorder<N> {
+    fn num_velocity_constraints(&self, manifold: &ColliderContactManifold<N>) -> usize {
+        self.model.num_velocity_constraints(manifold)
+    }
+
+    fn constraints(
+        &mut self,
+        params: &IntegrationParameters<N>,
+        bodies: &BodySet<N>,
+        ext_vels: &DVector<N>,
+        manifolds: &[ColliderContactManifold<N>],
+        ground_j_id: &mut usize,
+        j_id: &mut usize,
+        jacobians: &mut [N],
+        constraints: &mut ConstraintSet<N>,
+    ) {
+        let mut next_ground = constraints.velocity.unilateral_ground.len();
+        let mut next_free = constraints.velocity.unilateral.len();
+        self.model.constraints(
+            params,
+            bodies,
+            ext_vels,
+            manifolds,
+            ground_j_id,
+            j_id,
+            jacobians,
+            constraints,
+        );
+
+        // resolve the constraint of every contact the same way the inner model creates
+        // them; contacts still separated by more than the margins are skipped
+        let mut pending = Vec::with_capacity(manifolds.len());
+        for manifold in manifolds {
+            let ndofs = |handle| {
+                bodies
+                    .body(handle)
+                    .map_or(0, |body| body.status_dependent_ndofs())
+            };
+            let ground = ndofs(manifold.body1()) == 0 || ndofs(manifold.body2()) == 0;
+            let margins = manifold.collider1.margin() + manifold.collider2.margin();
+            let contacts = manifold
+                .contacts()
+                .map(|tracked| {
+                    if tracked.contact.depth + margins <= N::zero() {
+                        ContactConstraint::Inactive
+                    } else if ground {
+                        next_ground += 1;
+                        ContactConstraint::Ground(next_ground - 1)
+                    } else {
+                        next_free += 1;
+                        ContactConstraint::Free(next_free - 1)
+                    }
+                })
+                .collect();
+            pending.push((
+                (manifold.collider1.handle(), manifold.collider2.handle()),
+                contacts,
+            ));
+        }
+
+        if next_ground == constraints.velocity.unilateral_ground.len()
+            && next_free == constraints.velocity.unilateral.len()
+        {
+            self.pending.extend(pending);
+        } else {
+            debug!("Contact constraints do not match the contacts, skipping their impulses");
+        }
+    }
+
+    fn cache_impulses(&mut self, constraints: &ConstraintSet<N>) {
+        self.model.cache_impulses(constraints);
+
+        let mut impulses = self.impulses.impulses.lock().unwrap();
+        for (handles, contacts) in self.pending.drain(..) {
+            let contact_impulses = contacts
+                .into_iter()
+                .map(|constraint| match constraint {
+                    ContactConstraint::Ground(i) => {
+                        constraints.velocity.unilateral_ground[i].impulse
+                    }
+                    ContactConstraint::Free(i) => constraints.velocity.unilateral[i].impulse,
+                    ContactConstraint::Inactive => N::zero(),
+                })
+                .collect();
+            impulses.insert(handles, contact_impulses);
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use std::{error::Error, fmt};
+
+use amethyst::{ecs::Entity, shrev::EventChannel};
+
+/// The `PhysicsError`s published whenever the physics `System`s have to skip
+/// an `Entity` because its `Component`s cannot be synchronised with the
+/// `PhysicsWorld`. `PhysicsBody`s and `PhysicsCollider`s that cannot be
+/// created yet are kept pending and reported once when they become pending;
+/// all other errors are reported on every frame for as long as the invalid
+/// state persists.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PhysicsError {
+    /// The `PhysicsBody` or `PhysicsCollider` of the `Entity` cannot be
+    /// positioned in the `PhysicsWorld`, as the `Entity` has no `Transform`.
+    MissingTransform { entity: Entity },
+    /// The `PhysicsBody` of the `Entity` has no `RigidBody` in the
+    /// `PhysicsWorld`.
+    MissingRigidBody { entity: Entity },
+    /// The `PhysicsCollider` of the `Entity` has no `Collider` in the
+    /// `PhysicsWorld`.
+    MissingCollider { entity: Entity },
+    /// The `Collider` of the `Entity` could not be attached to its parent
+    /// `RigidBody`.
+    ColliderCreation { entity: Entity },
+}
+
+impl PhysicsError {
+    /// Returns the `Entity` the `PhysicsError` refers to.
+    pub fn entity(&self) -> Entity {
+        match *self {
+            PhysicsError::MissingTransform { entity }
+            | PhysicsError::MissingRigidBody { entity }
+            | PhysicsError::MissingCollider { entity }
+            | PhysicsError::ColliderCreation { entity } => entity,
+        }
+    }
+}
+
+impl fmt::Display for PhysicsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PhysicsError::MissingTransform { entity } => {
+                write!(f, "{:?} has no Transform to position it with", entity)
+            }
+            PhysicsError::MissingRigidBody { entity } => {
+                write!(f, "{:?} has no RigidBody in the PhysicsWorld", entity)
+            }
+            PhysicsError::MissingCollider { entity } => {
+                write!(f, "{:?} has no Collider in the PhysicsWorld", entity)
+            }
+            PhysicsError::ColliderCreation { entity } => {
+                write!(f, "failed to attach the Collider of {:?}", entity)
+            }
+        }
+    }
+}
+
+impl Error for PhysicsError {}
+
+/// Custom type alias for `EventChannel<PhysicsError>`.
+pub type PhysicsErrors = EventChannel<PhysicsError>;
@@ -0,0 +1,337 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use amethyst::ecs::{world::Index, Component, DenseVecStorage, Entity, FlaggedStorage};
+use ncollide::bounding_volume::AABB;
+pub use nphysics::math::{Force, ForceType};
+use nphysics::{
+    force_generator::{ForceGenerator, ForceGeneratorHandle},
+    object::{BodyHandle, BodySet},
+    solver::IntegrationParameters,
+};
+
+use crate::{
+    math::{self, RealField},
+    Point,
+    Vector,
+};
+
+/// The `HashMap` of `Index` to physics `ForceGeneratorHandle` mappings. This
+/// is used for the mapping of Amethyst `Entity`s based on their unique `Index`
+/// to `ForceGenerator`s created in the `PhysicsWorld`.
+pub type PhysicsForceFieldHandles = HashMap<Index, ForceGeneratorHandle>;
+
+/// The `ForceFieldRegion` defines which `RigidBody`s are affected by a
+/// `ForceField`. A `RigidBody` is inside the region if its centre of mass is.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ForceFieldRegion<N: RealField = f32> {
+    /// All `RigidBody`s within the given radius around the position of the
+    /// `ForceField` `Entity`.
+    Radius(N),
+    /// All `RigidBody`s inside the `PhysicsCollider` of the `ForceField`
+    /// `Entity`; usually a sensor.
+    Collider,
+}
+
+/// The `ForceFieldContext` describes the `ForceField` itself while the forces
+/// are computed.
+#[derive(Clone, Copy, Debug)]
+pub struct ForceFieldContext<N: RealField = f32> {
+    /// The world space position of the `ForceField` `Entity`.
+    pub position: Point<N>,
+    /// The radius of the region; for `ForceFieldRegion::Collider` this is the
+    /// radius of the bounding sphere of the `PhysicsCollider`.
+    pub radius: N,
+    /// The current `Gravity`.
+    pub gravity: Vector<N>,
+    /// The length of the upcoming step.
+    pub delta_time: N,
+}
+
+/// The `ForceFieldBody` describes a `RigidBody` inside the region of a
+/// `ForceField`.
+#[derive(Clone, Copy, Debug)]
+pub struct ForceFieldBody<N: RealField = f32> {
+    pub entity: Entity,
+    /// The world space centre of mass of the `RigidBody`.
+    pub center_of_mass: Point<N>,
+    /// The linear velocity of the `RigidBody`.
+    pub velocity: Vector<N>,
+    /// The world space bounding box of the `PhysicsCollider` of the `Entity`,
+    /// if present.
+    pub aabb: Option<AABB<N>>,
+}
+
+/// The `ForceFieldGenerator` computes the force a `ForceField` applies to a
+/// single `RigidBody` inside its region. Implement this trait for custom force
+/// fields.
+pub trait ForceFieldGenerator<N: RealField>: Send + Sync {
+    /// Returns the force and its `ForceType` applied to the given `RigidBody`
+    /// during the upcoming step.
+    fn force(
+        &mut self,
+        context: &ForceFieldContext<N>,
+        body: &ForceFieldBody<N>,
+    ) -> Option<(Force<N>, ForceType)>;
+
+    /// Called once after the forces of a step were computed.
+    fn step_finished(&mut self) {}
+
+    /// Checks whether the `ForceFieldGenerator` is exhausted. Finished
+    /// generators no longer apply any forces.
+    fn is_finished(&self) -> bool {
+        false
+    }
+}
+
+/// The `Wind` pushes `RigidBody`s towards its `velocity`. The applied force is
+/// proportional to the difference between the wind and body velocity.
+#[derive(Clone, Copy, Debug)]
+pub struct Wind<N: RealField = f32> {
+    pub velocity: Vector<N>,
+    pub drag: N,
+}
+
+impl<N: RealField> ForceFieldGenerator<N> for Wind<N> {
+    fn force(
+        &mut self,
+        _: &ForceFieldContext<N>,
+        body: &ForceFieldBody<N>,
+    ) -> Option<(Force<N>, ForceType)> {
+        Some((
+            Force::linear((self.velocity - body.velocity) * self.drag),
+            ForceType::Force,
+        ))
+    }
+}
+
+/// The `Falloff` of an `Explosion` based on the distance to its centre.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Falloff {
+    /// The full impulse is applied within the whole radius.
+    Constant,
+    /// The impulse decreases linearly towards the radius.
+    Linear,
+    /// The impulse decreases quadratically towards the radius.
+    Quadratic,
+}
+
+/// The `Explosion` applies a single radial impulse burst to all `RigidBody`s
+/// inside its region, pushing them away from its centre.
+#[derive(Clone, Copy, Debug)]
+pub struct Explosion<N: RealField = f32> {
+    pub impulse: N,
+    pub falloff: Falloff,
+    exploded: bool,
+}
+
+impl<N: RealField> Explosion<N> {
+    /// Creates a new `Explosion` with the given maximum impulse.
+    pub fn new(impulse: N, falloff: Falloff) -> Self {
+        Self {
+            impulse,
+            falloff,
+            exploded: false,
+        }
+    }
+}
+
+impl<N: RealField> ForceFieldGenerator<N> for Explosion<N> {
+    fn force(
+        &mut self,
+        context: &ForceFieldContext<N>,
+        body: &ForceFieldBody<N>,
+    ) -> Option<(Force<N>, ForceType)> {
+        let offset = body.center_of_mass - context.position;
+        let distance = offset.norm();
+        if distance >= context.radius || distance <= N::default_epsilon() {
+            return None;
+        }
+
+        let remaining = N::one() - distance / context.radius;
+        let factor = match self.falloff {
+            Falloff::Constant => N::one(),
+            Falloff::Linear => remaining,
+            Falloff::Quadratic => remaining * remaining,
+        };
+
+        Some((
+            Force::linear(offset / distance * self.impulse * factor),
+            ForceType::Impulse,
+        ))
+    }
+
+    fn step_finished(&mut self) {
+        self.exploded = true;
+    }
+
+    fn is_finished(&self) -> bool {
+        self.exploded
+    }
+}
+
+/// The `Buoyancy` lets `RigidBody`s float in a fluid below `surface_height`
+/// (measured along the y axis). The displaced volume is approximated by the
+/// part of the `PhysicsCollider` bounding box below the surface; the `damping`
+/// slows down submerged `RigidBody`s. `RigidBody`s without `PhysicsCollider`
+/// are not affected.
+#[derive(Clone, Copy, Debug)]
+pub struct Buoyancy<N: RealField = f32> {
+    pub fluid_density: N,
+    pub surface_height: N,
+    pub damping: N,
+}
+
+impl<N: RealField> ForceFieldGenerator<N> for Buoyancy<N> {
+    fn force(
+        &mut self,
+        context: &ForceFieldContext<N>,
+        body: &ForceFieldBody<N>,
+    ) -> Option<(Force<N>, ForceType)> {
+        let aabb = body.aabb.as_ref()?;
+        let height = aabb.maxs().y - aabb.mins().y;
+        if height <= N::zero() || aabb.mins().y >= self.surface_height {
+            return None;
+        }
+
+        let submerged = ((self.surface_height - aabb.mins().y) / height).min(N::one());
+        let volume = aabb
+            .half_extents()
+            .iter()
+            .fold(N::one(), |volume, half_extent| {
+                volume * *half_extent * math::convert(2.0)
+            });
+
+        let buoyancy = -context.gravity * self.fluid_density * volume * submerged;
+        let damping = -body.velocity * self.damping * submerged;
+
+        Some((Force::linear(buoyancy + damping), ForceType::Force))
+    }
+}
+
+/// The `ForceField` `Component` applies the forces of its
+/// `ForceFieldGenerator` to all `RigidBody`s inside its `ForceFieldRegion`.
+/// The position of the `ForceField` is taken from the world space `Transform`
+/// of the same `Entity`. Every `ForceField` maps onto a nphysics
+/// `ForceGenerator` which is added to and removed from the `PhysicsWorld`
+/// together with the `Component`.
+///
+/// For more information on how the forces are applied, see the following
+/// `System`:
+/// - `systems::force_fields::ForceFieldsSystem`
+///
+/// # Example
+///
+/// ```rust
+/// use game_physics::{
+///     force_field::{ForceField, ForceFieldRegion, Wind},
+///     Vector,
+/// };
+///
+/// let force_field = ForceField::<f32>::new(
+///     ForceFieldRegion::Radius(50.0),
+///     Wind {
+///         velocity: Vector::new(10.0, 0.0, 0.0),
+///         drag: 0.5,
+///     },
+/// );
+/// ```
+pub struct ForceField<N: RealField = f32> {
+    pub region: ForceFieldRegion<N>,
+    pub(crate) generator: Box<dyn ForceFieldGenerator<N>>,
+    pub(crate) forces: ForceFieldForces<N>,
+}
+
+impl<N: RealField> Component for ForceField<N> {
+    type Storage = FlaggedStorage<Self, DenseVecStorage<Self>>;
+}
+
+impl<N: RealField> ForceField<N> {
+    /// Creates a new `ForceField` for the given region and generator.
+    pub fn new<G: ForceFieldGenerator<N> + 'static>(
+        region: ForceFieldRegion<N>,
+        generator: G,
+    ) -> Self {
+        Self {
+            region,
+            generator: Box::new(generator),
+            forces: ForceFieldForces::default(),
+        }
+    }
+
+    /// Creates a new `Wind` `ForceField`.
+    pub fn wind(region: ForceFieldRegion<N>, velocity: Vector<N>, drag: N) -> Self {
+        Self::new(region, Wind { velocity, drag })
+    }
+
+    /// Creates a new `Explosion` `ForceField`.
+    pub fn explosion(region: ForceFieldRegion<N>, impulse: N, falloff: Falloff) -> Self {
+        Self::new(region, Explosion::new(impulse, falloff))
+    }
+
+    /// Creates a new `Buoyancy` `ForceField`.
+    pub fn buoyancy(
+        region: ForceFieldRegion<N>,
+        fluid_density: N,
+        surface_height: N,
+        damping: N,
+    ) -> Self {
+        Self::new(
+            region,
+            Buoyancy {
+                fluid_density,
+                surface_height,
+                damping,
+            },
+        )
+    }
+
+    /// Checks whether the `ForceFieldGenerator` is exhausted.
+    pub fn is_finished(&self) -> bool {
+        self.generator.is_finished()
+    }
+}
+
+/// The `ForceFieldForces` are the nphysics `ForceGenerator` of a `ForceField`;
+/// the `GravityZonesSystem` uses one to apply the resolved gravity. The forces
+/// are computed by the `System`s before every step and applied by the
+/// `PhysicsWorld` during the step.
+pub(crate) struct ForceFieldForces<N: RealField> {
+    forces: Arc<Mutex<Vec<(BodyHandle, Force<N>, ForceType)>>>,
+}
+
+impl<N: RealField> Clone for ForceFieldForces<N> {
+    fn clone(&self) -> Self {
+        Self {
+            forces: self.forces.clone(),
+        }
+    }
+}
+
+impl<N: RealField> Default for ForceFieldForces<N> {
+    fn default() -> Self {
+        Self {
+            forces: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+impl<N: RealField> ForceFieldForces<N> {
+    /// Replaces the forces applied during the upcoming step.
+    pub(crate) fn set(&self, forces: Vec<(BodyHandle, Force<N>, ForceType)>) {
+        *self.forces.lock().unwrap() = forces;
+    }
+}
+
+impl<N: RealField> ForceGenerator<N> for ForceFieldForces<N> {
+    fn apply(&mut self, _: &IntegrationParameters<N>, bodies: &mut BodySet<N>) -> bool {
+        for (handle, force, force_type) in self.forces.lock().unwrap().drain(..) {
+            if let Some(body) = bodies.body_mut(handle) {
+                body.apply_force(0, &force, force_type, true);
+            }
+        }
+        true
+    }
+}
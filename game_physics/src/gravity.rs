@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use amethyst::ecs::{Component, DenseVecStorage};
+use nphysics::object::BodyHandle;
+
+use crate::{math::RealField, Point, Vector};
+
+/// The `GravityModel` defines the gravity a `GravityZone` applies to the
+/// `RigidBody`s inside of it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GravityModel<N: RealField = f32> {
+    /// A constant gravity vector, e.g. for low gravity rooms.
+    Directional(Vector<N>),
+    /// A gravity of constant magnitude pointing towards the centre of the
+    /// `PhysicsCollider` of the `GravityZone`, e.g. for planetoids.
+    PointAttractor(N),
+}
+
+/// The `GravityCombine` defines how the gravity of a `GravityZone` is combined
+/// with the gravity resolved so far.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GravityCombine {
+    /// Replaces the global `Gravity` and the gravity of all `GravityZone`s with
+    /// a lower priority.
+    Override,
+    /// Adds the gravity of the `GravityZone` on top.
+    Additive,
+}
+
+/// The gravity resolved by the `GravityZonesSystem` for every dynamic
+/// `RigidBody` that deviates from the global `Gravity`, either because it is
+/// inside a `GravityZone` or because of its `gravity_scale`. `RigidBody`s
+/// without an entry are affected by the global `Gravity`.
+pub(crate) type ResolvedGravities<N> = HashMap<BodyHandle, Vector<N>>;
+
+/// The `GravityZone` `Component` changes the gravity of all `RigidBody`s whose
+/// centre of mass is inside the `PhysicsCollider` of the same `Entity`; usually
+/// a sensor. `GravityZone`s without `PhysicsCollider` have no effect.
+///
+/// Overlapping zones are applied in ascending order of their `priority`, ties
+/// are broken by the `Entity` id. Starting with the global `Gravity`, every
+/// `GravityCombine::Override` zone replaces the gravity resolved so far and
+/// every `GravityCombine::Additive` zone adds to it. The result is finally
+/// multiplied by the `gravity_scale` of the `PhysicsBody`.
+///
+/// For more information on how the gravity is applied, see the following
+/// `System`:
+/// - `systems::gravity_zones::GravityZonesSystem`
+///
+/// # Example
+///
+/// ```rust
+/// use game_physics::gravity::{GravityCombine, GravityModel, GravityZone};
+///
+/// let planetoid = GravityZone::<f32>::new(GravityModel::PointAttractor(9.81))
+///     .with_combine(GravityCombine::Override)
+///     .with_priority(1);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GravityZone<N: RealField = f32> {
+    pub model: GravityModel<N>,
+    pub combine: GravityCombine,
+    pub priority: i32,
+}
+
+impl<N: RealField> Component for GravityZone<N> {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl<N: RealField> GravityZone<N> {
+    /// Creates a new overriding `GravityZone` with priority `0`.
+    pub fn new(model: GravityModel<N>) -> Self {
+        Self {
+            model,
+            combine: GravityCombine::Override,
+            priority: 0,
+        }
+    }
+
+    /// Creates a new overriding `GravityZone` with a directional gravity.
+    pub fn directional(gravity: Vector<N>) -> Self {
+        Self::new(GravityModel::Directional(gravity))
+    }
+
+    /// Creates a new overriding `GravityZone` attracting `RigidBody`s towards
+    /// its centre.
+    pub fn point_attractor(strength: N) -> Self {
+        Self::new(GravityModel::PointAttractor(strength))
+    }
+
+    /// Sets the `GravityCombine` of the `GravityZone`.
+    pub fn with_combine(mut self, combine: GravityCombine) -> Self {
+        self.combine = combine;
+        self
+    }
+
+    /// Sets the priority of the `GravityZone`.
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Returns the gravity applied at the given `position` for a zone centred
+    /// at `center`.
+    pub(crate) fn gravity(&self, center: &Point<N>, position: &Point<N>) -> Vector<N> {
+        match self.model {
+            GravityModel::Directional(gravity) => gravity,
+            GravityModel::PointAttractor(strength) => {
+                let offset = center - position;
+                let distance = offset.norm();
+                if distance <= N::default_epsilon() {
+                    Vector::zeros()
+                } else {
+                    offset / distance * strength
+                }
+            }
+        }
+    }
+}
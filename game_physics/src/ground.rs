@@ -0,0 +1,119 @@
+use amethyst::{
+    ecs::{Component, DenseVecStorage, Entity},
+    shrev::EventChannel,
+};
+
+use crate::{
+    math::{self, RealField},
+    Vector,
+};
+
+/// The `GroundState` `Component` tracks whether the `PhysicsCollider` of the
+/// same `Entity` stands on the ground. A contact counts as ground if its
+/// normal deviates at most `max_slope` (in radians) from the up direction,
+/// which is the opposite of the gravity acting on the `RigidBody` of the
+/// `Entity`, taking `GravityZone`s into account. After losing the ground, the
+/// `Entity` stays grounded for `coyote_time` seconds, e.g. to still allow
+/// jumping right after walking off a ledge. The `Component` is opt-in and is
+/// updated after every `PhysicsWorld` step; transitions are additionally
+/// published as `GroundEvent`s via the `GroundEventChannel`.
+///
+/// For more information on how the ground state is tracked, see the following
+/// `System`:
+/// - `systems::ground_states::GroundStatesSystem`
+///
+/// # Example
+///
+/// ```rust
+/// use game_physics::GroundState;
+///
+/// let ground_state = GroundState::<f32>::new(std::f32::consts::FRAC_PI_4, 0.1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct GroundState<N: RealField = f32> {
+    pub max_slope: N,
+    pub coyote_time: N,
+    pub(crate) grounded: bool,
+    pub(crate) touching: bool,
+    pub(crate) airborne_time: N,
+    pub(crate) entity: Option<Entity>,
+    pub(crate) normal: Option<Vector<N>>,
+    pub(crate) velocity: Vector<N>,
+}
+
+impl<N: RealField> Component for GroundState<N> {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl<N: RealField> Default for GroundState<N> {
+    fn default() -> Self {
+        Self::new(
+            math::convert(std::f64::consts::FRAC_PI_4),
+            math::convert(0.1),
+        )
+    }
+}
+
+impl<N: RealField> GroundState<N> {
+    /// Creates a new `GroundState` with the given maximum slope angle in
+    /// radians and coyote time in seconds.
+    pub fn new(max_slope: N, coyote_time: N) -> Self {
+        Self {
+            max_slope,
+            coyote_time,
+            grounded: false,
+            touching: false,
+            airborne_time: N::zero(),
+            entity: None,
+            normal: None,
+            velocity: Vector::zeros(),
+        }
+    }
+
+    /// Checks whether the `Entity` is grounded, including the coyote time.
+    pub fn is_grounded(&self) -> bool {
+        self.grounded
+    }
+
+    /// Checks whether the `Entity` actually touched the ground during the
+    /// last step.
+    pub fn is_touching_ground(&self) -> bool {
+        self.touching
+    }
+
+    /// Returns the ground `Entity` touched during the last step.
+    pub fn ground_entity(&self) -> Option<Entity> {
+        self.entity
+    }
+
+    /// Returns the normal of the ground touched during the last step,
+    /// pointing away from the ground.
+    pub fn ground_normal(&self) -> Option<Vector<N>> {
+        self.normal
+    }
+
+    /// Returns the velocity of the ground at the contact point during the last
+    /// step, e.g. of a moving or rotating platform. Static ground has no
+    /// velocity.
+    pub fn ground_velocity(&self) -> Vector<N> {
+        self.velocity
+    }
+
+    /// Returns the time in seconds since the `Entity` last touched the
+    /// ground.
+    pub fn airborne_time(&self) -> N {
+        self.airborne_time
+    }
+}
+
+/// The `GroundEvent`s published whenever an `Entity` with a `GroundState`
+/// lands on or leaves the ground. Leaving is only reported once the coyote
+/// time passed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GroundEvent {
+    Landed { entity: Entity, ground: Entity },
+    Left { entity: Entity },
+}
+
+/// Custom type alias for `EventChannel<GroundEvent>`.
+pub type GroundEventChannel = EventChannel<GroundEvent>;
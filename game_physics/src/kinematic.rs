@@ -0,0 +1,235 @@
+use amethyst::{
+    ecs::{Component, DenseVecStorage, Entity},
+    shrev::EventChannel,
+};
+
+use crate::{
+    math::{self, RealField},
+    Vector,
+};
+
+/// The `Easing` applied to the motion between two waypoints of a
+/// `KinematicPath`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Easing {
+    /// Constant speed between two waypoints.
+    Linear,
+    /// Accelerates when leaving a waypoint.
+    EaseIn,
+    /// Decelerates when approaching a waypoint.
+    EaseOut,
+    /// Accelerates when leaving and decelerates when approaching a waypoint.
+    EaseInOut,
+}
+
+impl Easing {
+    /// Maps the linear progress `t` (in `[0, 1]`) between two waypoints to the
+    /// eased progress.
+    pub fn apply<N: RealField>(self, t: N) -> N {
+        let two: N = math::convert(2.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => t * (two - t),
+            Easing::EaseInOut => t * t * (math::convert::<_, N>(3.0) - two * t),
+        }
+    }
+}
+
+/// The `PathMode` defines what happens when a `KinematicPath` reaches its last
+/// waypoint.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PathMode {
+    /// Stops at the last waypoint.
+    Once,
+    /// Continues from the last to the first waypoint.
+    Loop,
+    /// Reverses the direction and travels the waypoints backwards.
+    PingPong,
+}
+
+/// The `KinematicPath` `Component` moves the `RigidBody` of the same `Entity`
+/// along a list of waypoints. The `RigidBody` should be created with
+/// `BodyStatus::Kinematic` and start at the first waypoint.
+///
+/// The motion is applied as velocity of the `RigidBody`, which allows dynamic
+/// `RigidBody`s resting on it to be carried along (e.g. moving platforms or
+/// elevators). Reaching a waypoint publishes a `KinematicPathEvent` via the
+/// `KinematicPathEventChannel`.
+///
+/// For more information on how the path is followed, see the following
+/// `System`:
+/// - `systems::kinematic_paths::KinematicPathsSystem`
+///
+/// # Example
+///
+/// ```rust
+/// use game_physics::{
+///     kinematic::{Easing, KinematicPath, PathMode},
+///     Vector,
+/// };
+///
+/// let kinematic_path = KinematicPath::<f32>::new(
+///     vec![Vector::new(0.0, 0.0, 0.0), Vector::new(0.0, 50.0, 0.0)],
+///     10.0,
+/// )
+/// .with_easing(Easing::EaseInOut)
+/// .with_mode(PathMode::PingPong);
+/// ```
+#[derive(Clone, Debug)]
+pub struct KinematicPath<N: RealField = f32> {
+    waypoints: Vec<Vector<N>>,
+    pub speed: N,
+    pub easing: Easing,
+    pub mode: PathMode,
+    pub(crate) from: usize,
+    pub(crate) to: usize,
+    pub(crate) progress: N,
+    pub(crate) forward: bool,
+    pub(crate) finished: bool,
+}
+
+impl<N: RealField> Component for KinematicPath<N> {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl<N: RealField> KinematicPath<N> {
+    /// Creates a new `KinematicPath` along the given waypoints with the given
+    /// speed in units per second.
+    pub fn new(waypoints: Vec<Vector<N>>, speed: N) -> Self {
+        Self {
+            waypoints,
+            speed,
+            easing: Easing::Linear,
+            mode: PathMode::Once,
+            from: 0,
+            to: 1,
+            progress: N::zero(),
+            forward: true,
+            finished: false,
+        }
+    }
+
+    /// Sets the `Easing` between two waypoints.
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Sets the `PathMode` applied at the last waypoint.
+    pub fn with_mode(mut self, mode: PathMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Returns the waypoints of the `KinematicPath`.
+    pub fn waypoints(&self) -> &[Vector<N>] {
+        &self.waypoints
+    }
+
+    /// Replaces the waypoints and restarts the `KinematicPath` from the first
+    /// of them.
+    pub fn set_waypoints(&mut self, waypoints: Vec<Vector<N>>) {
+        self.waypoints = waypoints;
+        self.restart();
+    }
+
+    /// Returns the index of the waypoint the `KinematicPath` is heading to.
+    pub fn target_waypoint(&self) -> usize {
+        self.to
+    }
+
+    /// Checks whether the `KinematicPath` reached its last waypoint in
+    /// `PathMode::Once`.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Restarts the `KinematicPath` from its first waypoint.
+    pub fn restart(&mut self) {
+        self.from = 0;
+        self.to = 1;
+        self.progress = N::zero();
+        self.forward = true;
+        self.finished = false;
+    }
+
+    /// Returns the current position on the `KinematicPath`, taking the
+    /// `Easing` into account.
+    pub(crate) fn position(&self) -> Option<Vector<N>> {
+        let from = self.waypoints.get(self.from)?;
+        if self.finished {
+            return Some(*from);
+        }
+        let to = self.waypoints.get(self.to)?;
+
+        Some(from + (to - from) * self.easing.apply(self.progress))
+    }
+
+    /// Advances the `KinematicPath` by the given time step and returns the
+    /// indices of all waypoints reached during that time.
+    pub(crate) fn advance(&mut self, delta_time: N) -> Vec<usize> {
+        let mut reached = Vec::new();
+        if self.finished || self.waypoints.len() < 2 {
+            return reached;
+        }
+
+        let mut distance = self.speed * delta_time;
+        // every waypoint can be reached at most once per step to guard against
+        // zero length segments
+        for _ in 0..self.waypoints.len() {
+            let length = (self.waypoints[self.to] - self.waypoints[self.from]).norm();
+            let remaining = length * (N::one() - self.progress);
+
+            if distance < remaining {
+                self.progress += distance / length;
+                break;
+            }
+
+            distance -= remaining;
+            reached.push(self.to);
+            self.next_segment();
+            if self.finished {
+                break;
+            }
+        }
+
+        reached
+    }
+
+    /// Moves on to the next segment according to the `PathMode`.
+    fn next_segment(&mut self) {
+        let last = self.waypoints.len() - 1;
+        self.progress = N::zero();
+        self.from = self.to;
+
+        match self.mode {
+            PathMode::Once if self.to == last => self.finished = true,
+            PathMode::Once => self.to += 1,
+            PathMode::Loop => self.to = (self.to + 1) % self.waypoints.len(),
+            PathMode::PingPong => {
+                if (self.forward && self.to == last) || (!self.forward && self.to == 0) {
+                    self.forward = !self.forward;
+                }
+                if self.forward {
+                    self.to += 1;
+                } else {
+                    self.to -= 1;
+                }
+            }
+        }
+    }
+}
+
+/// The `KinematicPathEvent`s published while `Entity`s follow their
+/// `KinematicPath`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum KinematicPathEvent {
+    /// The `Entity` reached the waypoint with the given index.
+    WaypointReached { entity: Entity, waypoint: usize },
+    /// The `Entity` reached the last waypoint of a `PathMode::Once` path.
+    Finished { entity: Entity },
+}
+
+/// Custom type alias for `EventChannel<KinematicPathEvent>`.
+pub type KinematicPathEventChannel = EventChannel<KinematicPathEvent>;
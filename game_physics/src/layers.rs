@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+use ncollide::world::CollisionGroups;
+use serde::{Deserialize, Serialize};
+
+use crate::{collider::PhysicsCollider, math::RealField};
+
+/// The `CollisionLayers` `Resource` maps human readable layer names (e.g.
+/// `"player"` or `"terrain"`) to `CollisionGroups` indices and defines which
+/// layers interact with each other. The interaction matrix is symmetric: two
+/// layers interact if either of them lists the other one.
+///
+/// `PhysicsCollider`s reference layers by name via
+/// `PhysicsColliderBuilder::layer(..)` and
+/// `PhysicsColliderBuilder::collides_with(..)`; the names are resolved to
+/// `CollisionGroups` when the `Collider` is created or updated.
+///
+/// `CollisionLayers` are usually loaded from a RON file using Amethysts
+/// `Config` trait and handed to `PhysicsBundle::with_collision_layers(..)`:
+///
+/// ```ron
+/// (
+///     layers: {
+///         "player": 0,
+///         "enemy": 1,
+///         "terrain": 2,
+///         "pickup": 3,
+///     },
+///     interactions: {
+///         "player": ["enemy", "terrain", "pickup"],
+///         "enemy": ["terrain"],
+///     },
+/// )
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct CollisionLayers {
+    layers: HashMap<String, usize>,
+    #[serde(default)]
+    interactions: HashMap<String, Vec<String>>,
+}
+
+impl CollisionLayers {
+    /// Adds a new layer with the given name and `CollisionGroups` index.
+    pub fn with_layer(mut self, layer: &str, group_id: usize) -> Self {
+        self.layers.insert(layer.to_string(), group_id);
+        self
+    }
+
+    /// Lets both given layers interact with each other.
+    pub fn with_interaction(mut self, layer: &str, other: &str) -> Self {
+        self.interactions
+            .entry(layer.to_string())
+            .or_default()
+            .push(other.to_string());
+        self
+    }
+
+    /// Returns the `CollisionGroups` index of the given layer. Unknown layers
+    /// and indices exceeding `CollisionGroups::max_group_id()` return `None`.
+    pub fn group_id(&self, layer: &str) -> Option<usize> {
+        self.layers
+            .get(layer)
+            .cloned()
+            .filter(|group_id| *group_id <= CollisionGroups::max_group_id())
+    }
+
+    /// Checks whether both given layers interact with each other.
+    pub fn interacts(&self, layer: &str, other: &str) -> bool {
+        let lists = |layer: &str, other: &str| {
+            self.interactions
+                .get(layer)
+                .map_or(false, |others| others.iter().any(|name| name == other))
+        };
+
+        lists(layer, other) || lists(other, layer)
+    }
+
+    /// Resolves the given layer to `CollisionGroups`. The `CollisionGroups`
+    /// are a member of the layers group only and interact with all layers
+    /// given in `collides_with` or, if not present, with all layers defined
+    /// by the interaction matrix. Unknown layer names are ignored.
+    pub fn collision_groups(
+        &self,
+        layer: &str,
+        collides_with: Option<&[String]>,
+    ) -> Option<CollisionGroups> {
+        let group_id = self.group_id(layer)?;
+
+        let whitelist = match collides_with {
+            Some(others) => others
+                .iter()
+                .filter_map(|other| {
+                    let other_group_id = self.group_id(other);
+                    if other_group_id.is_none() {
+                        warn!("Unknown collision layer: {}", other);
+                    }
+                    other_group_id
+                })
+                .collect::<Vec<usize>>(),
+            None => self
+                .layers
+                .keys()
+                .filter(|other| self.interacts(layer, other))
+                .filter_map(|other| self.group_id(other))
+                .collect::<Vec<usize>>(),
+        };
+
+        Some(
+            CollisionGroups::new()
+                .with_membership(&[group_id])
+                .with_whitelist(&whitelist),
+        )
+    }
+
+    /// Returns the `CollisionGroups` of a `PhysicsCollider`, resolving its
+    /// layer names if present. `PhysicsCollider`s without or with an unknown
+    /// layer keep their `collision_groups`.
+    pub(crate) fn resolve<N: RealField>(
+        &self,
+        physics_collider: &PhysicsCollider<N>,
+    ) -> CollisionGroups {
+        let layer = match physics_collider.layer {
+            Some(ref layer) => layer,
+            None => return physics_collider.collision_groups,
+        };
+
+        let collides_with = physics_collider.collides_with.as_ref().map(Vec::as_slice);
+        self.collision_groups(layer, collides_with)
+            .unwrap_or_else(|| {
+                warn!("Unknown collision layer: {}", layer);
+                physics_collider.collision_groups
+            })
+    }
+}
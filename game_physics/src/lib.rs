@@ -2,32 +2,79 @@
 /// https://github.com/distransient/nphysics-ecs-dumb
 #[macro_use]
 extern crate log;
+#[cfg(feature = "dim2")]
+extern crate ncollide2d as ncollide;
+#[cfg(feature = "dim3")]
 extern crate ncollide3d as ncollide;
+#[cfg(feature = "dim2")]
+extern crate nphysics2d as nphysics;
+#[cfg(feature = "dim3")]
 extern crate nphysics3d as nphysics;
 
+#[cfg(all(feature = "dim2", feature = "dim3"))]
+compile_error!("The features `dim2` and `dim3` are mutually exclusive.");
+#[cfg(not(any(feature = "dim2", feature = "dim3")))]
+compile_error!("Either the `dim2` or the `dim3` feature has to be enabled.");
+
 use std::collections::HashMap;
 
 use amethyst::ecs::world::Index;
 pub use nalgebra as math;
+/// The dimension dependent math types of nphysics. Depending on the enabled
+/// feature (`dim2` or `dim3`) these resolve to their 2D or 3D variants.
+pub use nphysics::math::{AngularInertia, Isometry, Point, Vector};
 use nphysics::{
     object::{BodyHandle, ColliderHandle},
     world::World,
 };
 
-use self::math::Vector3;
 pub use self::{
+    auto_collider::{AutoCollider, AutoShape},
     body::{PhysicsBody, PhysicsBodyBuilder},
     collider::{PhysicsCollider, PhysicsColliderBuilder, Shape},
+    contact_filter::{ContactFilter, ContactFilterPair, OneWayPlatform},
+    contacts::Contacts,
+    error::{PhysicsError, PhysicsErrors},
+    force_field::{ForceField, ForceFieldGenerator, ForceFieldRegion},
+    gravity::GravityZone,
+    ground::{GroundEvent, GroundEventChannel, GroundState},
+    kinematic::{KinematicPath, KinematicPathEvent, KinematicPathEventChannel},
+    layers::CollisionLayers,
+    materials::{PhysicsMaterial, PhysicsMaterials},
+    sleep::{SleepEvent, SleepEventChannel, SleepRequest, SleepRequestChannel, Sleeping},
+    snapshot::PhysicsSnapshot,
+    stats::{PhysicsStats, SyncStats},
     systems::PhysicsBundle,
+    tile_grid::{Tile, TileGrid},
+    trigger::{TriggerEvent, TriggerEventChannel, TriggerVolume},
+    world_hash::PhysicsWorldHash,
 };
 
+pub mod auto_collider;
 pub mod body;
 pub mod collider;
+pub mod contact_filter;
+pub mod contacts;
+pub mod error;
+pub mod force_field;
+pub mod gravity;
+pub mod ground;
+pub mod kinematic;
+pub mod layers;
+pub mod materials;
+pub mod sleep;
+pub mod snapshot;
+pub mod stats;
 mod systems;
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+pub mod tile_grid;
+pub mod trigger;
+pub mod world_hash;
 
 /// The `PhysicsWorld` containing all physical objects.
-pub type PhysicsWorld = World<f32>;
+pub type PhysicsWorld<N = f32> = World<N>;
 
-/// `Gravity` is a type alias for `Vector3<f32>`. It represents a constant
+/// `Gravity` is a type alias for `Vector<N>`. It represents a constant
 /// acceleration affecting all physical objects in the scene.
-pub type Gravity = Vector3<f32>;
+pub type Gravity<N = f32> = Vector<N>;
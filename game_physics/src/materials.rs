@@ -0,0 +1,208 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::Path,
+    sync::{Arc, RwLock},
+};
+
+use amethyst::error::Error;
+use nphysics::material::{
+    BasicMaterial,
+    LocalMaterialProperties,
+    Material,
+    MaterialCombineMode,
+    MaterialContext,
+    MaterialHandle,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{math::RealField, Vector};
+
+/// The `CombineMode` defines how the friction or restitution coefficients of
+/// two touching `PhysicsCollider`s are combined. If both `PhysicsCollider`s
+/// use different modes, the mode with the highest precedence wins
+/// (`Average` < `Min` < `Multiply` < `Max`).
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum CombineMode {
+    Average,
+    Min,
+    Multiply,
+    Max,
+}
+
+impl Default for CombineMode {
+    fn default() -> Self {
+        CombineMode::Average
+    }
+}
+
+impl From<CombineMode> for MaterialCombineMode {
+    fn from(combine_mode: CombineMode) -> Self {
+        match combine_mode {
+            CombineMode::Average => MaterialCombineMode::Average,
+            CombineMode::Min => MaterialCombineMode::Min,
+            CombineMode::Multiply => MaterialCombineMode::Multiply,
+            CombineMode::Max => MaterialCombineMode::Max,
+        }
+    }
+}
+
+/// The `PhysicsMaterial` describes the surface properties of a named material
+/// in the `PhysicsMaterials` library. A `surface_velocity` can be used to
+/// simulate conveyor belts and similar moving surfaces.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PhysicsMaterial<N: RealField = f32> {
+    pub friction: N,
+    pub restitution: N,
+    #[serde(default)]
+    pub friction_combine_mode: CombineMode,
+    #[serde(default)]
+    pub restitution_combine_mode: CombineMode,
+    #[serde(default)]
+    pub surface_velocity: Option<Vector<N>>,
+}
+
+impl<N: RealField> PhysicsMaterial<N> {
+    /// Creates a new `PhysicsMaterial` with the given `friction` and
+    /// `restitution` values.
+    pub fn new(friction: N, restitution: N) -> Self {
+        Self {
+            friction,
+            restitution,
+            friction_combine_mode: CombineMode::default(),
+            restitution_combine_mode: CombineMode::default(),
+            surface_velocity: None,
+        }
+    }
+
+    /// Converts the `PhysicsMaterial` into its nphysics `BasicMaterial`
+    /// counterpart.
+    fn basic_material(&self) -> BasicMaterial<N> {
+        let mut basic_material = BasicMaterial::new(self.restitution, self.friction);
+        basic_material.friction_combine_mode = self.friction_combine_mode.into();
+        basic_material.restitution_combine_mode = self.restitution_combine_mode.into();
+        basic_material.surface_velocity = self.surface_velocity;
+        basic_material
+    }
+}
+
+/// The `SharedMaterial` is the `Material` behind the `MaterialHandle`s handed
+/// out by `PhysicsMaterials`. All `Collider`s using the same named material
+/// share one `SharedMaterial`, which allows to update their properties in
+/// place when the `PhysicsMaterials` are reloaded.
+struct SharedMaterial<N: RealField>(Arc<RwLock<BasicMaterial<N>>>);
+
+impl<N: RealField> Material<N> for SharedMaterial<N> {
+    fn local_properties(&self, context: MaterialContext<N>) -> LocalMaterialProperties<N> {
+        self.0.read().unwrap().local_properties(context)
+    }
+}
+
+/// The `PhysicsMaterials` `Resource` is a library of named
+/// `PhysicsMaterial`s. `PhysicsCollider`s reference materials by name via
+/// `PhysicsColliderBuilder::material_named(..)`; all `PhysicsCollider`s using
+/// the same name share a single `MaterialHandle`.
+///
+/// Reloading the `PhysicsMaterials` updates the shared `MaterialHandle`s in
+/// place, so existing `Collider`s pick up the new values without being
+/// recreated. `PhysicsBundle::with_materials_from_file(..)` loads the library
+/// from a RON file and reloads it whenever the file changes:
+///
+/// ```ron
+/// (
+///     materials: {
+///         "ice": (
+///             friction: 0.02,
+///             restitution: 0.1,
+///             friction_combine_mode: Min,
+///         ),
+///         "conveyor": (
+///             friction: 0.8,
+///             restitution: 0.0,
+///             surface_velocity: Some((5.0, 0.0, 0.0)),
+///         ),
+///     },
+/// )
+/// ```
+#[derive(Deserialize, Serialize)]
+pub struct PhysicsMaterials<N: RealField = f32> {
+    materials: HashMap<String, PhysicsMaterial<N>>,
+    #[serde(skip)]
+    handles: HashMap<String, (Arc<RwLock<BasicMaterial<N>>>, MaterialHandle<N>)>,
+}
+
+impl<N: RealField> Default for PhysicsMaterials<N> {
+    fn default() -> Self {
+        Self {
+            materials: HashMap::new(),
+            handles: HashMap::new(),
+        }
+    }
+}
+
+impl<N: RealField> PhysicsMaterials<N> {
+    /// Adds the given `PhysicsMaterial` under the given name.
+    pub fn with_material(mut self, name: &str, material: PhysicsMaterial<N>) -> Self {
+        self.insert(name, material);
+        self
+    }
+
+    /// Inserts or replaces the `PhysicsMaterial` with the given name. The
+    /// `MaterialHandle` of a replaced material is updated in place.
+    pub fn insert(&mut self, name: &str, material: PhysicsMaterial<N>) {
+        if let Some((shared, _)) = self.handles.get(name) {
+            *shared.write().unwrap() = material.basic_material();
+        }
+        self.materials.insert(name.to_string(), material);
+    }
+
+    /// Returns the `PhysicsMaterial` with the given name.
+    pub fn get(&self, name: &str) -> Option<&PhysicsMaterial<N>> {
+        self.materials.get(name)
+    }
+
+    /// Returns the shared `MaterialHandle` of the `PhysicsMaterial` with the
+    /// given name, creating it on first use.
+    pub fn handle(&mut self, name: &str) -> Option<MaterialHandle<N>> {
+        let material = self.materials.get(name)?;
+
+        let (_, handle) = self.handles.entry(name.to_string()).or_insert_with(|| {
+            let shared = Arc::new(RwLock::new(material.basic_material()));
+            let handle = MaterialHandle::new(SharedMaterial(shared.clone()));
+            (shared, handle)
+        });
+
+        Some(handle.clone())
+    }
+
+    /// Replaces all `PhysicsMaterial`s with the ones of the given
+    /// `PhysicsMaterials`. Existing `MaterialHandle`s are updated in place;
+    /// handles of materials missing in the new library keep their last values.
+    pub fn reload(&mut self, other: PhysicsMaterials<N>) {
+        for name in self.materials.keys() {
+            if !other.materials.contains_key(name) {
+                warn!("Physics material removed on reload: {}", name);
+            }
+        }
+
+        self.materials.clear();
+        for (name, material) in other.materials {
+            self.insert(&name, material);
+        }
+    }
+}
+
+impl<N> PhysicsMaterials<N>
+where
+    N: RealField + DeserializeOwned,
+{
+    /// Deserialises `PhysicsMaterials` from a RON string.
+    pub fn from_ron(ron: &str) -> Result<Self, Error> {
+        Ok(ron::de::from_str(ron)?)
+    }
+
+    /// Loads `PhysicsMaterials` from the RON file at the given path.
+    pub fn from_file(path: &Path) -> Result<Self, Error> {
+        Self::from_ron(&fs::read_to_string(path)?)
+    }
+}
@@ -0,0 +1,50 @@
+use amethyst::{
+    ecs::{Component, Entity, NullStorage},
+    shrev::EventChannel,
+};
+
+use crate::{math::RealField, Point};
+
+/// The `Sleeping` marker `Component` is present on all `Entity`s whose dynamic
+/// `RigidBody` is currently asleep. It is maintained by the physics `System`s
+/// after each step and should be treated as read-only; use `SleepRequest`s to
+/// put a `RigidBody` to sleep or wake it up.
+///
+/// For more information on how the sleep states are tracked, see the following
+/// `System`s:
+/// - `systems::sleep_requests::SleepRequestsSystem`
+/// - `systems::sleep_states::SleepStatesSystem`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Sleeping;
+
+impl Component for Sleeping {
+    type Storage = NullStorage<Self>;
+}
+
+/// The `SleepEvent`s published whenever a dynamic `RigidBody` falls asleep or
+/// wakes up.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SleepEvent {
+    Asleep { entity: Entity },
+    Awake { entity: Entity },
+}
+
+/// Custom type alias for `EventChannel<SleepEvent>`.
+pub type SleepEventChannel = EventChannel<SleepEvent>;
+
+/// The `SleepRequest`s allow to explicitly wake up or put to sleep the
+/// `RigidBody`s of `Entity`s. Requests are handled right before the next
+/// `PhysicsWorld` step.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SleepRequest<N: RealField = f32> {
+    /// Wakes up the `RigidBody` of the given `Entity`.
+    Wake { entity: Entity },
+    /// Puts the `RigidBody` of the given `Entity` to sleep.
+    Sleep { entity: Entity },
+    /// Wakes up all `RigidBody`s with a `Collider` intersecting the axis
+    /// aligned bounding box spanned by `mins` and `maxs`.
+    WakeArea { mins: Point<N>, maxs: Point<N> },
+}
+
+/// Custom type alias for `EventChannel<SleepRequest<N>>`.
+pub type SleepRequestChannel<N = f32> = EventChannel<SleepRequest<N>>;
@@ -0,0 +1,613 @@
+use std::collections::{HashMap, HashSet};
+
+use amethyst::{
+    core::{Parent, Transform},
+    ecs::{world::Index, Entities, Entity, Join, ReadStorage, World, WriteStorage},
+    error::Error,
+};
+use ncollide::world::CollisionGroups;
+use nphysics::{
+    math::{SpatialVector, Velocity},
+    object::{Body, BodyStatus, RigidBody},
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    body::{PhysicsBody, PhysicsBodyBuilder, PhysicsBodyHandles},
+    collider::{PhysicsCollider, PhysicsColliderBuilder, PhysicsColliderHandles, Shape},
+    math::RealField,
+    systems::{apply_isometry, hierarchy_depth, local_isometry},
+    AngularInertia,
+    Isometry,
+    PhysicsWorld,
+    Point,
+    Vector,
+};
+
+/// The `PhysicsSnapshot` contains the complete state of the `PhysicsWorld`
+/// (body positions, velocities and sleep states as well as the set of
+/// colliders) at the time it was captured. The state of every `RigidBody` and
+/// `Collider` is keyed by the `Entity` it belongs to.
+///
+/// A `PhysicsSnapshot` can be serialised into a compact binary format (for
+/// rollback buffers) or into RON (for save games and debugging).
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let snapshot = PhysicsSnapshot::capture(&world);
+/// let bytes = snapshot.to_bytes()?;
+///
+/// // ... some time later ...
+///
+/// PhysicsSnapshot::from_bytes(&bytes)?.restore(&mut world);
+/// ```
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PhysicsSnapshot<N: RealField = f32> {
+    pub bodies: Vec<BodySnapshot<N>>,
+    pub colliders: Vec<ColliderSnapshot<N>>,
+}
+
+impl<N: RealField> Default for PhysicsSnapshot<N> {
+    fn default() -> Self {
+        Self {
+            bodies: Vec::new(),
+            colliders: Vec::new(),
+        }
+    }
+}
+
+/// The `EntityKey` identifies an `Entity` in a `PhysicsSnapshot` by its
+/// `Index` and `Generation`. Using both values prevents a snapshot from being
+/// restored onto a different `Entity` that reused the same `Index`.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct EntityKey {
+    pub id: Index,
+    pub generation: i32,
+}
+
+impl From<Entity> for EntityKey {
+    fn from(entity: Entity) -> Self {
+        Self {
+            id: entity.id(),
+            generation: entity.gen().id(),
+        }
+    }
+}
+
+/// The `BodySnapshot` holds the `PhysicsBody` `Component` and the simulated
+/// state of its `RigidBody` for a single `Entity`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BodySnapshot<N: RealField> {
+    pub entity: EntityKey,
+    pub gravity_enabled: bool,
+    #[serde(default = "default_gravity_scale")]
+    pub gravity_scale: N,
+    pub body_status: BodyStatusSnapshot,
+    pub velocity: Vector<N>,
+    pub angular_inertia: AngularInertia<N>,
+    pub mass: N,
+    pub local_center_of_mass: Point<N>,
+    #[serde(default)]
+    pub mass_from_colliders: bool,
+    #[serde(default)]
+    pub ccd_motion_threshold: Option<N>,
+    pub position: Isometry<N>,
+    /// The linear and angular velocity of the `RigidBody`, see
+    /// `Velocity::as_vector()`.
+    pub rigid_body_velocity: SpatialVector<N>,
+    pub sleeping: bool,
+}
+
+/// The default `gravity_scale` of snapshots captured before it existed.
+fn default_gravity_scale<N: RealField>() -> N {
+    N::one()
+}
+
+/// The simulated state of a `RigidBody` restored from a `PhysicsSnapshot`
+/// while its `PhysicsBody` had to be re-inserted. The `SyncBodiesSystem`
+/// applies it as soon as it creates the `RigidBody` of the `Entity`.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct RestoredBodyState<N: RealField> {
+    pub(crate) velocity: Velocity<N>,
+    pub(crate) sleeping: bool,
+}
+
+impl<N: RealField> RestoredBodyState<N> {
+    /// Applies the velocity and sleep state to the given `RigidBody`.
+    pub(crate) fn apply(&self, rigid_body: &mut RigidBody<N>) {
+        rigid_body.set_velocity(self.velocity);
+        if self.sleeping {
+            rigid_body.deactivate();
+        } else {
+            rigid_body.activate();
+        }
+    }
+}
+
+/// The `RestoredBodyState`s waiting for their `RigidBody` to be created.
+pub(crate) type RestoredBodyStates<N> = HashMap<Entity, RestoredBodyState<N>>;
+
+/// Serialisable mirror of the nphysics `BodyStatus`.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum BodyStatusSnapshot {
+    Disabled,
+    Static,
+    Dynamic,
+    Kinematic,
+}
+
+impl From<BodyStatus> for BodyStatusSnapshot {
+    fn from(body_status: BodyStatus) -> Self {
+        match body_status {
+            BodyStatus::Disabled => BodyStatusSnapshot::Disabled,
+            BodyStatus::Static => BodyStatusSnapshot::Static,
+            BodyStatus::Dynamic => BodyStatusSnapshot::Dynamic,
+            BodyStatus::Kinematic => BodyStatusSnapshot::Kinematic,
+        }
+    }
+}
+
+impl From<BodyStatusSnapshot> for BodyStatus {
+    fn from(body_status: BodyStatusSnapshot) -> Self {
+        match body_status {
+            BodyStatusSnapshot::Disabled => BodyStatus::Disabled,
+            BodyStatusSnapshot::Static => BodyStatus::Static,
+            BodyStatusSnapshot::Dynamic => BodyStatus::Dynamic,
+            BodyStatusSnapshot::Kinematic => BodyStatus::Kinematic,
+        }
+    }
+}
+
+/// The `ColliderSnapshot` holds the `PhysicsCollider` `Component` and the
+/// position of its `Collider` for a single `Entity`. The `MaterialHandle` is
+/// not part of the snapshot, but the name of the material is: restored named
+/// materials are resolved via the `PhysicsMaterials` again, unnamed ones keep
+/// their current material unless the `PhysicsCollider` has to be replaced and
+/// fall back to the default one otherwise.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ColliderSnapshot<N: RealField> {
+    pub entity: EntityKey,
+    pub shape: Shape<N>,
+    pub offset_from_parent: Isometry<N>,
+    pub density: N,
+    #[serde(default)]
+    pub material_name: Option<String>,
+    pub margin: N,
+    pub collision_groups: CollisionGroupsSnapshot,
+    #[serde(default)]
+    pub layer: Option<String>,
+    #[serde(default)]
+    pub collides_with: Option<Vec<String>>,
+    pub linear_prediction: N,
+    pub angular_prediction: N,
+    pub sensor: bool,
+    pub position: Isometry<N>,
+}
+
+/// Serialisable mirror of the ncollide `CollisionGroups`, stored as bit masks.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CollisionGroupsSnapshot {
+    pub membership: u32,
+    pub whitelist: u32,
+    pub blacklist: u32,
+}
+
+impl From<CollisionGroups> for CollisionGroupsSnapshot {
+    fn from(collision_groups: CollisionGroups) -> Self {
+        let mut snapshot = Self {
+            membership: 0,
+            whitelist: 0,
+            blacklist: 0,
+        };
+
+        for group_id in 0..=CollisionGroups::max_group_id() {
+            if collision_groups.is_member_of(group_id) {
+                snapshot.membership |= 1 << group_id;
+            }
+            if collision_groups.is_group_whitelisted(group_id) {
+                snapshot.whitelist |= 1 << group_id;
+            }
+            if collision_groups.is_group_blacklisted(group_id) {
+                snapshot.blacklist |= 1 << group_id;
+            }
+        }
+
+        snapshot
+    }
+}
+
+impl From<CollisionGroupsSnapshot> for CollisionGroups {
+    fn from(snapshot: CollisionGroupsSnapshot) -> Self {
+        let groups = |mask: u32| -> Vec<usize> {
+            (0..=CollisionGroups::max_group_id())
+                .filter(|group_id| mask & (1 << group_id) != 0)
+                .collect()
+        };
+
+        CollisionGroups::new()
+            .with_membership(&groups(snapshot.membership))
+            .with_whitelist(&groups(snapshot.whitelist))
+            .with_blacklist(&groups(snapshot.blacklist))
+    }
+}
+
+impl<N: RealField> PhysicsSnapshot<N> {
+    /// Captures the current state of all `PhysicsBody` and `PhysicsCollider`
+    /// `Component`s and their `PhysicsWorld` counterparts. Components that
+    /// were not yet synchronised with the `PhysicsWorld` are skipped.
+    pub fn capture(world: &World) -> Self {
+        let (entities, physics_bodies, physics_colliders): (
+            Entities,
+            ReadStorage<PhysicsBody<N>>,
+            ReadStorage<PhysicsCollider<N>>,
+        ) = world.system_data();
+        let physics_world = world.read_resource::<PhysicsWorld<N>>();
+        let physics_body_handles = world.read_resource::<PhysicsBodyHandles>();
+        let physics_collider_handles = world.read_resource::<PhysicsColliderHandles>();
+
+        let mut snapshot = Self::default();
+
+        for (entity, physics_body) in (&entities, &physics_bodies).join() {
+            let rigid_body = match physics_body_handles
+                .get(&entity.id())
+                .and_then(|handle| physics_world.rigid_body(*handle))
+            {
+                Some(rigid_body) => rigid_body,
+                None => continue,
+            };
+
+            snapshot.bodies.push(BodySnapshot {
+                entity: EntityKey::from(entity),
+                gravity_enabled: physics_body.gravity_enabled,
+                gravity_scale: physics_body.gravity_scale,
+                body_status: physics_body.body_status.into(),
+                angular_inertia: physics_body.angular_inertia,
+                mass: physics_body.mass,
+                velocity: physics_body.velocity,
+                local_center_of_mass: physics_body.local_center_of_mass,
+                mass_from_colliders: physics_body.mass_from_colliders,
+                ccd_motion_threshold: physics_body.ccd_motion_threshold,
+                position: *rigid_body.position(),
+                rigid_body_velocity: *rigid_body.velocity().as_vector(),
+                sleeping: !rigid_body.is_active(),
+            });
+        }
+
+        for (entity, physics_collider) in (&entities, &physics_colliders).join() {
+            let collider = match physics_collider_handles
+                .get(&entity.id())
+                .and_then(|handle| physics_world.collider(*handle))
+            {
+                Some(collider) => collider,
+                None => continue,
+            };
+
+            snapshot.colliders.push(ColliderSnapshot {
+                entity: EntityKey::from(entity),
+                shape: physics_collider.shape.clone(),
+                offset_from_parent: physics_collider.offset_from_parent,
+                density: physics_collider.density,
+                material_name: physics_collider.material_name.clone(),
+                margin: physics_collider.margin,
+                collision_groups: physics_collider.collision_groups.into(),
+                layer: physics_collider.layer.clone(),
+                collides_with: physics_collider.collides_with.clone(),
+                linear_prediction: physics_collider.linear_prediction,
+                angular_prediction: physics_collider.angular_prediction,
+                sensor: physics_collider.sensor,
+                position: *collider.position(),
+            });
+        }
+
+        snapshot
+    }
+
+    /// Restores the state stored in this `PhysicsSnapshot`:
+    /// - `PhysicsBody` and `PhysicsCollider` `Component`s of entities that are
+    ///   not part of the snapshot are removed
+    /// - missing `PhysicsBody` and `PhysicsCollider` `Component`s are
+    ///   re-inserted and created by the synchronisation `System`s during the
+    ///   next dispatch; the captured velocities and sleep states are applied
+    ///   to the re-created `RigidBody`s
+    /// - existing `RigidBody`s and `Collider`s are moved to their captured
+    ///   positions and get their velocities and sleep states restored
+    /// - `PhysicsCollider` `Component`s whose shape, offset, density, material,
+    ///   margin, predictions or sensor flag changed are replaced, so their
+    ///   `Collider`s are rebuilt during the next dispatch
+    ///
+    /// The `Transform` of every restored body is updated as well. Entities
+    /// that were deleted since the snapshot was captured are skipped.
+    pub fn restore(&self, world: &mut World) {
+        self.restore_bodies(world);
+        self.restore_colliders(world);
+    }
+
+    fn restore_bodies(&self, world: &mut World) {
+        world
+            .res
+            .entry::<RestoredBodyStates<N>>()
+            .or_insert_with(RestoredBodyStates::<N>::new);
+
+        let (entities, parents, mut physics_bodies, mut transforms): (
+            Entities,
+            ReadStorage<Parent>,
+            WriteStorage<PhysicsBody<N>>,
+            WriteStorage<Transform>,
+        ) = world.system_data();
+        let mut physics_world = world.write_resource::<PhysicsWorld<N>>();
+        let physics_body_handles = world.read_resource::<PhysicsBodyHandles>();
+        let mut restored_body_states = world.write_resource::<RestoredBodyStates<N>>();
+
+        // remove PhysicsBody components that did not exist at capture time
+        let captured = self
+            .bodies
+            .iter()
+            .map(|body| body.entity)
+            .collect::<HashSet<EntityKey>>();
+        let obsolete = (&entities, &physics_bodies)
+            .join()
+            .map(|(entity, _)| entity)
+            .filter(|entity| !captured.contains(&EntityKey::from(*entity)))
+            .collect::<Vec<Entity>>();
+        for entity in obsolete {
+            physics_bodies.remove(entity);
+        }
+
+        // restore the bodies sorted by their depth in the Parent hierarchy; parents have
+        // to be restored before their children are placed relative to them
+        let mut bodies = self
+            .bodies
+            .iter()
+            .filter_map(|body| match alive_entity(&entities, body.entity) {
+                Some(entity) => Some((hierarchy_depth(entity, &parents), body, entity)),
+                None => {
+                    warn!("Skipping restore of deleted entity: {:?}", body.entity);
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        bodies.sort_by_key(|(depth, ..)| *depth);
+
+        for (_, body, entity) in bodies {
+            // restore the Transform first so missing bodies are created at their captured
+            // position; the captured world space position is converted into the local
+            // space of the parent Entity, if any
+            if !transforms.contains(entity) {
+                if let Err(err) = transforms.insert(entity, Transform::default()) {
+                    error!("Failed to restore Transform for {:?}: {}", entity, err);
+                    continue;
+                }
+            }
+            let position = local_isometry(entity, &body.position, &parents, &transforms);
+            if let Some(transform) = transforms.get_mut(entity) {
+                apply_isometry(&position, transform);
+            }
+
+            let (handle, modified) = match physics_bodies.get(entity) {
+                Some(physics_body) => {
+                    // only touch the PhysicsBody if its values differ from the snapshot
+                    let modified = !body.matches(physics_body);
+                    if modified {
+                        let physics_body = physics_bodies.get_mut(entity).unwrap();
+                        body.apply(physics_body);
+                    }
+                    (physics_body_handles.get(&entity.id()).cloned(), modified)
+                }
+                None => {
+                    // re-insert missing PhysicsBody components; the SyncBodiesSystem
+                    // creates their RigidBodys during the next dispatch and applies the
+                    // queued simulated state
+                    let mut physics_body =
+                        PhysicsBodyBuilder::from(BodyStatus::from(body.body_status)).build();
+                    body.apply(&mut physics_body);
+                    if let Err(err) = physics_bodies.insert(entity, physics_body) {
+                        error!("Failed to restore PhysicsBody for {:?}: {}", entity, err);
+                        continue;
+                    }
+                    (None, false)
+                }
+            };
+
+            // restore the simulated state of the RigidBody if it already exists; it is
+            // queued until the SyncBodiesSystem created the RigidBody or applied the
+            // modified PhysicsBody, which would override the restored velocity otherwise
+            let state = RestoredBodyState {
+                velocity: Velocity::from_vector(&body.rigid_body_velocity),
+                sleeping: body.sleeping,
+            };
+            match handle.and_then(|handle| physics_world.rigid_body_mut(handle)) {
+                Some(rigid_body) => {
+                    rigid_body.set_position(body.position);
+                    state.apply(rigid_body);
+                    if modified {
+                        restored_body_states.insert(entity, state);
+                    }
+                }
+                None => {
+                    restored_body_states.insert(entity, state);
+                }
+            }
+        }
+    }
+
+    fn restore_colliders(&self, world: &mut World) {
+        let (entities, mut physics_colliders): (Entities, WriteStorage<PhysicsCollider<N>>) =
+            world.system_data();
+        let mut physics_world = world.write_resource::<PhysicsWorld<N>>();
+        let physics_collider_handles = world.read_resource::<PhysicsColliderHandles>();
+
+        // remove PhysicsCollider components that did not exist at capture time
+        let captured = self
+            .colliders
+            .iter()
+            .map(|collider| collider.entity)
+            .collect::<HashSet<EntityKey>>();
+        let obsolete = (&entities, &physics_colliders)
+            .join()
+            .map(|(entity, _)| entity)
+            .filter(|entity| !captured.contains(&EntityKey::from(*entity)))
+            .collect::<Vec<Entity>>();
+        for entity in obsolete {
+            physics_colliders.remove(entity);
+        }
+
+        for collider in &self.colliders {
+            let entity = match alive_entity(&entities, collider.entity) {
+                Some(entity) => entity,
+                None => {
+                    warn!("Skipping restore of deleted entity: {:?}", collider.entity);
+                    continue;
+                }
+            };
+
+            let replace = match physics_colliders.get(entity) {
+                Some(physics_collider) if collider.shape_matches(physics_collider) => {
+                    // the SyncCollidersSystem updates the collision groups in place
+                    if !collider.matches(physics_collider) {
+                        let physics_collider = physics_colliders.get_mut(entity).unwrap();
+                        collider.apply(physics_collider);
+                    }
+
+                    // restore the position of the Collider if it already exists
+                    if let Some(handle) = physics_collider_handles.get(&entity.id()) {
+                        physics_world
+                            .collider_world_mut()
+                            .set_position(*handle, collider.position);
+                    }
+                    false
+                }
+                // all other values cannot be changed on an existing Collider, so the
+                // PhysicsCollider is replaced and its Collider rebuilt
+                Some(_) => {
+                    physics_colliders.remove(entity);
+                    true
+                }
+                None => true,
+            };
+
+            if replace {
+                // re-insert missing or replaced PhysicsCollider components; the
+                // SyncCollidersSystem creates their Colliders during the next dispatch
+                let mut physics_collider =
+                    PhysicsColliderBuilder::from(collider.shape.clone()).build();
+                collider.apply(&mut physics_collider);
+                if let Err(err) = physics_colliders.insert(entity, physics_collider) {
+                    error!(
+                        "Failed to restore PhysicsCollider for {:?}: {}",
+                        entity, err
+                    );
+                }
+            }
+        }
+    }
+}
+
+impl<N> PhysicsSnapshot<N>
+where
+    N: RealField + Serialize + DeserializeOwned,
+{
+    /// Serialises the `PhysicsSnapshot` into a compact binary representation.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        Ok(bincode::serialize(self)?)
+    }
+
+    /// Deserialises a `PhysicsSnapshot` from its binary representation.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(bincode::deserialize(bytes)?)
+    }
+
+    /// Serialises the `PhysicsSnapshot` into a RON string.
+    pub fn to_ron(&self) -> Result<String, Error> {
+        Ok(ron::ser::to_string_pretty(
+            self,
+            ron::ser::PrettyConfig::default(),
+        )?)
+    }
+
+    /// Deserialises a `PhysicsSnapshot` from a RON string.
+    pub fn from_ron(ron: &str) -> Result<Self, Error> {
+        Ok(ron::de::from_str(ron)?)
+    }
+}
+
+impl<N: RealField> BodySnapshot<N> {
+    /// Checks whether the `PhysicsBody` values equal the captured ones.
+    fn matches(&self, physics_body: &PhysicsBody<N>) -> bool {
+        physics_body.gravity_enabled == self.gravity_enabled
+            && physics_body.gravity_scale == self.gravity_scale
+            && BodyStatusSnapshot::from(physics_body.body_status) == self.body_status
+            && physics_body.velocity == self.velocity
+            && physics_body.angular_inertia == self.angular_inertia
+            && physics_body.mass == self.mass
+            && physics_body.local_center_of_mass == self.local_center_of_mass
+            && physics_body.mass_from_colliders == self.mass_from_colliders
+            && physics_body.ccd_motion_threshold == self.ccd_motion_threshold
+    }
+
+    /// Overwrites the `PhysicsBody` values with the captured ones.
+    fn apply(&self, physics_body: &mut PhysicsBody<N>) {
+        physics_body.gravity_enabled = self.gravity_enabled;
+        physics_body.gravity_scale = self.gravity_scale;
+        physics_body.body_status = self.body_status.into();
+        physics_body.velocity = self.velocity;
+        physics_body.angular_inertia = self.angular_inertia;
+        physics_body.mass = self.mass;
+        physics_body.local_center_of_mass = self.local_center_of_mass;
+        physics_body.mass_from_colliders = self.mass_from_colliders;
+        physics_body.ccd_motion_threshold = self.ccd_motion_threshold;
+    }
+}
+
+impl<N: RealField> ColliderSnapshot<N> {
+    /// Checks whether the `PhysicsCollider` values equal the captured ones.
+    fn matches(&self, physics_collider: &PhysicsCollider<N>) -> bool {
+        self.shape_matches(physics_collider)
+            && CollisionGroupsSnapshot::from(physics_collider.collision_groups)
+                == self.collision_groups
+            && physics_collider.layer == self.layer
+            && physics_collider.collides_with == self.collides_with
+    }
+
+    /// Checks whether the `PhysicsCollider` values a `Collider` is built with
+    /// equal the captured ones. Unlike the collision groups, they cannot be
+    /// changed on an existing `Collider`.
+    fn shape_matches(&self, physics_collider: &PhysicsCollider<N>) -> bool {
+        physics_collider.shape == self.shape
+            && physics_collider.offset_from_parent == self.offset_from_parent
+            && physics_collider.density == self.density
+            && physics_collider.material_name == self.material_name
+            && physics_collider.margin == self.margin
+            && physics_collider.linear_prediction == self.linear_prediction
+            && physics_collider.angular_prediction == self.angular_prediction
+            && physics_collider.sensor == self.sensor
+    }
+
+    /// Overwrites the `PhysicsCollider` values with the captured ones. The
+    /// `MaterialHandle` is left untouched; the `SyncCollidersSystem` resolves
+    /// the restored `material_name` when it creates the `Collider`.
+    fn apply(&self, physics_collider: &mut PhysicsCollider<N>) {
+        physics_collider.shape = self.shape.clone();
+        physics_collider.offset_from_parent = self.offset_from_parent;
+        physics_collider.density = self.density;
+        physics_collider.material_name = self.material_name.clone();
+        physics_collider.margin = self.margin;
+        physics_collider.collision_groups = self.collision_groups.into();
+        physics_collider.layer = self.layer.clone();
+        physics_collider.collides_with = self.collides_with.clone();
+        physics_collider.linear_prediction = self.linear_prediction;
+        physics_collider.angular_prediction = self.angular_prediction;
+        physics_collider.sensor = self.sensor;
+    }
+}
+
+/// Returns the `Entity` for the given `EntityKey` if it is still alive and has
+/// not been replaced by a newer generation.
+fn alive_entity(entities: &Entities, key: EntityKey) -> Option<Entity> {
+    let entity = entities.entity(key.id);
+    if entities.is_alive(entity) && entity.gen().id() == key.generation {
+        Some(entity)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,97 @@
+use std::{fmt, time::Duration};
+
+/// The `SyncStats` describe a single batch of added, updated or removed
+/// `PhysicsBody` or `PhysicsCollider` `Component`s synchronised with the
+/// `PhysicsWorld`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SyncStats {
+    /// The wall-clock time spent on the batch.
+    pub time: Duration,
+    /// The number of `ComponentEvent`s processed in the batch.
+    pub events: usize,
+}
+
+/// The `PhysicsStats` `Resource` is updated with every step of the
+/// `PhysicsWorld` and provides insight into the size of the simulation and
+/// the time spent on it. The timings and event counts of the synchronised
+/// batches refer to the last run of the synchronisation `System`s before the
+/// step.
+///
+/// A summary of the `PhysicsStats` can be logged periodically; see
+/// `PhysicsBundle::with_stats_log`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PhysicsStats {
+    /// The number of steps since the `PhysicsStats` were created.
+    pub steps: u64,
+    pub dynamic_bodies: usize,
+    pub kinematic_bodies: usize,
+    pub static_bodies: usize,
+    pub disabled_bodies: usize,
+    /// The number of dynamic bodies that are currently asleep.
+    pub sleeping_bodies: usize,
+    /// The number of colliders, including sensors.
+    pub colliders: usize,
+    pub sensors: usize,
+    /// The number of collider pairs with at least one contact.
+    pub contact_pairs: usize,
+    pub add_rigid_bodies: SyncStats,
+    pub update_rigid_bodies: SyncStats,
+    pub remove_rigid_bodies: SyncStats,
+    pub add_colliders: SyncStats,
+    pub update_colliders: SyncStats,
+    pub remove_colliders: SyncStats,
+    /// The wall-clock time spent in `physics_world.step()`.
+    pub step_time: Duration,
+}
+
+impl PhysicsStats {
+    /// Returns the total number of bodies.
+    pub fn bodies(&self) -> usize {
+        self.dynamic_bodies + self.kinematic_bodies + self.static_bodies + self.disabled_bodies
+    }
+
+    /// Returns the total wall-clock time spent on the synchronised batches.
+    pub fn sync_time(&self) -> Duration {
+        self.sync_stats().iter().map(|stats| stats.time).sum()
+    }
+
+    /// Returns the total number of `ComponentEvent`s processed in the
+    /// synchronised batches.
+    pub fn sync_events(&self) -> usize {
+        self.sync_stats().iter().map(|stats| stats.events).sum()
+    }
+
+    fn sync_stats(&self) -> [SyncStats; 6] {
+        [
+            self.add_rigid_bodies,
+            self.update_rigid_bodies,
+            self.remove_rigid_bodies,
+            self.add_colliders,
+            self.update_colliders,
+            self.remove_colliders,
+        ]
+    }
+}
+
+impl fmt::Display for PhysicsStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "step {}: {} bodies ({} dynamic, {} sleeping, {} kinematic, {} static, {} disabled), \
+             {} colliders ({} sensors), {} contact pairs, sync: {:?} ({} events), step: {:?}",
+            self.steps,
+            self.bodies(),
+            self.dynamic_bodies,
+            self.sleeping_bodies,
+            self.kinematic_bodies,
+            self.static_bodies,
+            self.disabled_bodies,
+            self.colliders,
+            self.sensors,
+            self.contact_pairs,
+            self.sync_time(),
+            self.sync_events(),
+            self.step_time
+        )
+    }
+}
@@ -0,0 +1,148 @@
+use std::{collections::HashMap, marker::PhantomData, path::PathBuf};
+
+use amethyst::{
+    assets::AssetStorage,
+    ecs::{Entities, Entity, Join, Read, ReadStorage, Resources, System, SystemData, WriteStorage},
+    renderer::{sprite::SpriteRender, SpriteSheet},
+};
+use image::RgbaImage;
+
+use crate::{
+    auto_collider::{AutoCollider, AutoShape},
+    collider::PhysicsCollider,
+    math::{self, RealField},
+    Isometry,
+    Vector,
+};
+
+/// The `AutoCollidersSystem` replaces `AutoCollider` `Component`s with
+/// `PhysicsCollider`s derived from the `SpriteRender` of the same `Entity` as
+/// soon as the `SpriteSheet` is loaded. Decoded images are cached per path.
+///
+/// The image files of `AutoShape::ConvexHull`s are read and decoded
+/// synchronously, so the first frame deriving a hull from a not yet cached
+/// image is blocked until the file is loaded.
+///
+/// This `System` has to be executed before the `SyncCollidersSystem`.
+pub struct AutoCollidersSystem<N: RealField> {
+    images: HashMap<PathBuf, Option<RgbaImage>>,
+    marker: PhantomData<N>,
+}
+
+impl<N: RealField> Default for AutoCollidersSystem<N> {
+    fn default() -> Self {
+        Self {
+            images: HashMap::new(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'s, N: RealField> System<'s> for AutoCollidersSystem<N> {
+    type SystemData = (
+        Entities<'s>,
+        Read<'s, AssetStorage<SpriteSheet>>,
+        ReadStorage<'s, SpriteRender>,
+        WriteStorage<'s, AutoCollider<N>>,
+        WriteStorage<'s, PhysicsCollider<N>>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, sprite_sheets, sprite_renders, mut auto_colliders, mut physics_colliders) =
+            data;
+
+        let mut derived: Vec<(Entity, Option<PhysicsCollider<N>>)> = Vec::new();
+        for (entity, auto_collider, sprite_render) in
+            (&entities, &auto_colliders, &sprite_renders).join()
+        {
+            // wait until the SpriteSheet is loaded
+            let sprite_sheet = match sprite_sheets.get(&sprite_render.sprite_sheet) {
+                Some(sprite_sheet) => sprite_sheet,
+                None => continue,
+            };
+
+            let sprite = match sprite_sheet.sprites.get(sprite_render.sprite_number) {
+                Some(sprite) => sprite,
+                None => {
+                    warn!(
+                        "Unknown sprite {} for AutoCollider of {:?}",
+                        sprite_render.sprite_number, entity
+                    );
+                    derived.push((entity, None));
+                    continue;
+                }
+            };
+
+            let shape = match auto_collider.shape {
+                AutoShape::Rectangle => auto_collider.rectangle_shape(sprite),
+                AutoShape::ConvexHull {
+                    ref texture_path,
+                    alpha_threshold,
+                } => {
+                    // blocks on the first use of the image file; the result is cached
+                    let image =
+                        self.images.entry(texture_path.clone()).or_insert_with(
+                            || match image::open(texture_path) {
+                                Ok(image) => Some(image.to_rgba()),
+                                Err(err) => {
+                                    error!("Failed to load image {:?}: {}", texture_path, err);
+                                    None
+                                }
+                            },
+                        );
+
+                    // fall back to the sprite rectangle if no hull can be derived
+                    image
+                        .as_ref()
+                        .and_then(|image| {
+                            auto_collider.convex_hull_shape(sprite, image, alpha_threshold)
+                        })
+                        .unwrap_or_else(|| auto_collider.rectangle_shape(sprite))
+                }
+            };
+
+            // the sprite is rendered shifted by its offsets
+            let mut physics_collider = auto_collider.physics_collider.clone();
+            physics_collider.shape = shape;
+            physics_collider.offset_from_parent =
+                sprite_offset(&sprite.offsets) * physics_collider.offset_from_parent;
+            derived.push((entity, Some(physics_collider)));
+        }
+
+        for (entity, physics_collider) in derived {
+            auto_colliders.remove(entity);
+
+            if let Some(physics_collider) = physics_collider {
+                debug!(
+                    "Derived PhysicsCollider for {:?}: {:?}",
+                    entity, physics_collider
+                );
+                if let Err(err) = physics_colliders.insert(entity, physics_collider) {
+                    error!("Failed to insert PhysicsCollider for {:?}: {}", entity, err);
+                }
+            }
+        }
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        info!("AutoCollidersSystem.setup");
+        Self::SystemData::setup(res);
+    }
+}
+
+/// Converts the offsets of a `Sprite` into an `Isometry`.
+fn sprite_offset<N: RealField>(offsets: &[f32; 2]) -> Isometry<N> {
+    let (x, y): (N, N) = (
+        -math::convert::<f64, N>(f64::from(offsets[0])),
+        -math::convert::<f64, N>(f64::from(offsets[1])),
+    );
+
+    #[cfg(feature = "dim2")]
+    {
+        Isometry::new(Vector::new(x, y), N::zero())
+    }
+    #[cfg(feature = "dim3")]
+    {
+        Isometry::new(Vector::new(x, y, N::zero()), Vector::zeros())
+    }
+}
@@ -1,3 +0,0 @@
-pub mod add_rigid_bodies;
-pub mod remove_rigid_bodies;
-pub mod update_rigid_bodies;
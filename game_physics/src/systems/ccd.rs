@@ -0,0 +1,197 @@
+use std::marker::PhantomData;
+
+use amethyst::ecs::{
+    Entities,
+    Join,
+    ReadExpect,
+    ReadStorage,
+    Resources,
+    System,
+    SystemData,
+    WriteExpect,
+};
+use ncollide::{query::Ray, world::CollisionGroups};
+use nphysics::math::Velocity;
+
+use crate::{
+    body::{PhysicsBody, PhysicsBodyHandles},
+    collider::PhysicsColliderHandles,
+    math::RealField,
+    PhysicsWorld,
+    Point,
+    Vector,
+};
+
+/// The `CcdSystem` provides continuous collision detection for `PhysicsBody`s
+/// with a `ccd_motion_threshold`. Before every step, the expected motion of
+/// each such `RigidBody` is checked via ray casts from the centre and the
+/// corners of the AABB of its `Collider` in the direction of its linear
+/// velocity. If an obstacle would be passed during the step, the linear
+/// velocity along the normal of the obstacle is shortened so that the
+/// `RigidBody` stops right in front of it, while the tangential velocity is
+/// kept; the regular contact handling takes over in the following step.
+///
+/// This is an approximation based on ray interferences; the continuous
+/// collision detection of nphysics itself is not used. Thin obstacles between
+/// the rays can still be missed.
+///
+/// This `System` has to be executed right before the `PhysicsStepperSystem`.
+pub struct CcdSystem<N: RealField> {
+    marker: PhantomData<N>,
+}
+
+impl<N: RealField> Default for CcdSystem<N> {
+    fn default() -> Self {
+        Self {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'s, N: RealField> System<'s> for CcdSystem<N> {
+    type SystemData = (
+        Entities<'s>,
+        ReadExpect<'s, PhysicsBodyHandles>,
+        ReadExpect<'s, PhysicsColliderHandles>,
+        ReadStorage<'s, PhysicsBody<N>>,
+        WriteExpect<'s, PhysicsWorld<N>>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            physics_body_handles,
+            physics_collider_handles,
+            physics_bodies,
+            mut physics_world,
+        ) = data;
+
+        let delta_time = physics_world.timestep();
+
+        for (entity, physics_body) in (&entities, &physics_bodies).join() {
+            let motion_threshold = match physics_body.ccd_motion_threshold {
+                Some(motion_threshold) => motion_threshold,
+                None => continue,
+            };
+
+            let body_handle = match physics_body_handles.get(&entity.id()) {
+                Some(body_handle) => *body_handle,
+                None => continue,
+            };
+
+            let (position, velocity) = match physics_world.rigid_body(body_handle) {
+                Some(rigid_body) => (rigid_body.position().clone(), rigid_body.velocity().clone()),
+                None => continue,
+            };
+
+            // only check RigidBodies that move further than the threshold during this step
+            let distance = velocity.linear.norm() * delta_time;
+            if distance <= motion_threshold {
+                continue;
+            }
+            let direction: Vector<N> = velocity.linear.normalize();
+
+            // cast rays from the centre and the corners of the AABB of the Collider; the
+            // extent of the AABB ahead of each origin along the direction of motion is
+            // the distance the RigidBody can approach an obstacle hit by that ray
+            let collider = physics_collider_handles
+                .get(&entity.id())
+                .and_then(|handle| physics_world.collider(*handle));
+            let (rays, collision_groups) = match collider {
+                Some(collider) => {
+                    let aabb = collider.shape().aabb(collider.position());
+                    (
+                        aabb_rays(&aabb.center(), &aabb.half_extents(), &direction),
+                        *collider.collision_groups(),
+                    )
+                }
+                None => (
+                    vec![(Point::from(position.translation.vector), N::zero())],
+                    CollisionGroups::new(),
+                ),
+            };
+
+            // find the nearest obstacle over all rays, ignoring sensors and the Colliders
+            // attached to the RigidBody itself
+            let mut nearest: Option<(N, Vector<N>)> = None;
+            for (origin, extent) in rays {
+                let ray = Ray::new(origin, direction);
+                for (collider, intersection) in physics_world
+                    .collider_world()
+                    .interferences_with_ray(&ray, &collision_groups)
+                {
+                    if collider.body() == body_handle
+                        || collider.is_sensor()
+                        || intersection.toi >= distance + extent
+                    {
+                        continue;
+                    }
+
+                    let allowed_distance = (intersection.toi - extent).max(N::zero());
+                    if nearest.map_or(true, |(nearest, _)| allowed_distance < nearest) {
+                        nearest = Some((allowed_distance, intersection.normal));
+                    }
+                }
+            }
+
+            // only shorten the velocity along the normal of the obstacle, so the
+            // RigidBody keeps sliding along it
+            if let Some((allowed_distance, normal)) = nearest {
+                let approach = -velocity.linear.dot(&normal);
+                if approach <= N::zero() {
+                    continue;
+                }
+                debug!(
+                    "CCD clamped motion of {:?} from {} to {}",
+                    entity, distance, allowed_distance
+                );
+
+                let clamped = approach * (N::one() - allowed_distance / distance);
+                if let Some(rigid_body) = physics_world.rigid_body_mut(body_handle) {
+                    rigid_body.set_velocity(Velocity::new(
+                        velocity.linear + normal * clamped,
+                        velocity.angular,
+                    ));
+                }
+            }
+        }
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        info!("CcdSystem.setup");
+        Self::SystemData::setup(res);
+
+        // initialise required resources
+        res.entry::<PhysicsWorld<N>>()
+            .or_insert_with(PhysicsWorld::<N>::new);
+        res.entry::<PhysicsBodyHandles>()
+            .or_insert(PhysicsBodyHandles::new());
+        res.entry::<PhysicsColliderHandles>()
+            .or_insert(PhysicsColliderHandles::new());
+    }
+}
+
+/// Returns the origins of the rays cast for an AABB with the given centre and
+/// half extents, along with the extent of the AABB ahead of each origin in the
+/// given direction: the centre and all corners of the AABB.
+fn aabb_rays<N: RealField>(
+    center: &Point<N>,
+    half_extents: &Vector<N>,
+    direction: &Vector<N>,
+) -> Vec<(Point<N>, N)> {
+    let extent = half_extents.dot(&direction.abs());
+    let mut rays = vec![(*center, extent)];
+
+    let dimension = half_extents.len();
+    for mask in 0..(1 << dimension) {
+        let mut offset = *half_extents;
+        for axis in 0..dimension {
+            if mask & (1 << axis) != 0 {
+                offset[axis] = -offset[axis];
+            }
+        }
+        rays.push((center + offset, extent - offset.dot(direction)));
+    }
+
+    rays
+}
@@ -1,3 +0,0 @@
-pub mod add_colliders;
-pub mod remove_colliders;
-pub mod update_colliders;
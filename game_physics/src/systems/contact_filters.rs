@@ -0,0 +1,212 @@
+use std::{collections::HashMap, sync::Arc};
+
+use amethyst::ecs::{
+    storage::ComponentEvent,
+    world::Index,
+    Entities,
+    Join,
+    ReadExpect,
+    ReadStorage,
+    ReaderId,
+    Resources,
+    System,
+    SystemData,
+    WriteExpect,
+    WriteStorage,
+};
+use ncollide::{bounding_volume::BoundingVolume, world::CollisionGroups};
+use nphysics::object::{Body, BodyStatus, ColliderHandle};
+
+use crate::{
+    collider::{PhysicsCollider, PhysicsColliderHandles},
+    contact_filter::{ContactFilter, ContactFilterPairs, OneWayPlatform},
+    math::{self, RealField},
+    systems::component_changes,
+    PhysicsWorld,
+};
+
+/// The `ContactFiltersSystem` registers a broad phase pair filter with the
+/// `PhysicsWorld` that applies the `OneWayPlatform` `Component`s and the
+/// optional user provided `ContactFilter`. The `Entity`s of the `Collider`s
+/// and the `OneWayPlatform`s known to the filter are updated from the
+/// `ComponentEvent`s of the `PhysicsCollider` and `OneWayPlatform` storages.
+///
+/// The broad phase only evaluates a pair once its bounding boxes start to
+/// overlap; registering the filter anew makes it re-evaluate all overlapping
+/// pairs during the upcoming step, which is expensive. This is only done if:
+/// - a `OneWayPlatform` was inserted, modified or removed
+/// - a non-sensor `Collider` of an active, non-static `RigidBody` is close to
+///   a `OneWayPlatform`, as the side it approaches from has to be checked
+///   anew while it moves
+/// - the `ContactFilter` asks for it, see `ContactFilter::reevaluate`
+///
+/// Created or rebuilt `Collider`s need no re-evaluation, as their pairs are new
+/// to the broad phase anyway.
+///
+/// This `System` has to be executed before the `PhysicsStepperSystem`.
+pub struct ContactFiltersSystem<N: RealField> {
+    pairs: ContactFilterPairs<N>,
+    handles: HashMap<Index, ColliderHandle>,
+    physics_colliders_reader_id: Option<ReaderId<ComponentEvent>>,
+    one_way_platforms_reader_id: Option<ReaderId<ComponentEvent>>,
+}
+
+impl<N: RealField> ContactFiltersSystem<N> {
+    /// Creates a new `ContactFiltersSystem` with the given optional
+    /// `ContactFilter`.
+    pub fn new(filter: Option<Arc<dyn ContactFilter<N>>>) -> Self {
+        Self {
+            pairs: ContactFilterPairs::new(filter),
+            handles: HashMap::new(),
+            physics_colliders_reader_id: None,
+            one_way_platforms_reader_id: None,
+        }
+    }
+}
+
+impl<'s, N: RealField> System<'s> for ContactFiltersSystem<N> {
+    type SystemData = (
+        Entities<'s>,
+        ReadExpect<'s, PhysicsColliderHandles>,
+        WriteExpect<'s, PhysicsWorld<N>>,
+        ReadStorage<'s, PhysicsCollider<N>>,
+        ReadStorage<'s, OneWayPlatform<N>>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            physics_collider_handles,
+            mut physics_world,
+            physics_colliders,
+            one_way_platforms,
+        ) = data;
+
+        let mut state = self.pairs.state.lock().unwrap();
+
+        // track the Entity of every Collider; the SyncCollidersSystem modifies the
+        // PhysicsCollider whenever it creates or rebuilds its Collider
+        let changes = component_changes(
+            &physics_colliders,
+            self.physics_colliders_reader_id.as_mut().unwrap(),
+        );
+        for id in (&changes.removed).join() {
+            if let Some(handle) = self.handles.remove(&id) {
+                state.entities.remove(&handle);
+            }
+        }
+        for (entity, physics_collider, _) in (
+            &entities,
+            &physics_colliders,
+            &changes.inserted | &changes.modified,
+        )
+            .join()
+        {
+            let previous = match physics_collider.handle {
+                Some(handle) => self.handles.insert(entity.id(), handle),
+                None => self.handles.remove(&entity.id()),
+            };
+            if previous == physics_collider.handle {
+                continue;
+            }
+
+            // the previous handle may already belong to another Entity's Collider
+            if let Some(previous) = previous {
+                if state.entities.get(&previous) == Some(&entity) {
+                    state.entities.remove(&previous);
+                }
+            }
+            if let Some(handle) = physics_collider.handle {
+                state.entities.insert(handle, entity);
+            }
+        }
+
+        // track the normals of all OneWayPlatforms
+        let changes = component_changes(
+            &one_way_platforms,
+            self.one_way_platforms_reader_id.as_mut().unwrap(),
+        );
+        for id in (&changes.removed).join() {
+            state.one_way_platforms.remove(&id);
+        }
+        for (entity, one_way_platform, _) in (
+            &entities,
+            &one_way_platforms,
+            &changes.inserted | &changes.modified,
+        )
+            .join()
+        {
+            state
+                .one_way_platforms
+                .insert(entity.id(), one_way_platform.normal);
+        }
+        let mut platforms_changed = changes.inserted;
+        platforms_changed |= &changes.modified;
+        platforms_changed |= &changes.removed;
+
+        // moving Colliders close to a OneWayPlatform have to be checked anew
+        let collider_world = physics_world.collider_world();
+        let moving = |handle| {
+            physics_world
+                .rigid_body(handle)
+                .map_or(false, |rigid_body| {
+                    rigid_body.is_active() && rigid_body.status() != BodyStatus::Static
+                })
+        };
+        let platforms_approached = state.one_way_platforms.keys().any(|id| {
+            let handle = match physics_collider_handles.get(id) {
+                Some(handle) => *handle,
+                None => return false,
+            };
+            let collider = match collider_world.collider(handle) {
+                Some(collider) => collider,
+                None => return false,
+            };
+            let aabb = collider
+                .shape()
+                .aabb(collider.position())
+                .loosened(collider.margin() * math::convert(2.0));
+            collider_world
+                .interferences_with_aabb(&aabb, &CollisionGroups::new())
+                .any(|other| other.handle() != handle && !other.is_sensor() && moving(other.body()))
+        });
+
+        let reevaluate = !platforms_changed.is_empty()
+            || platforms_approached
+            || state
+                .filter
+                .as_ref()
+                .map_or(false, |filter| filter.reevaluate());
+        drop(state);
+
+        // registering the filter again re-evaluates all pairs during the next step
+        if reevaluate {
+            physics_world
+                .collider_world_mut()
+                .register_broad_phase_pair_filter("contact_filters", self.pairs.clone());
+        }
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        info!("ContactFiltersSystem.setup");
+        Self::SystemData::setup(res);
+
+        // initialise required resources
+        res.entry::<PhysicsWorld<N>>()
+            .or_insert_with(PhysicsWorld::<N>::new);
+        res.entry::<PhysicsColliderHandles>()
+            .or_insert(PhysicsColliderHandles::new());
+
+        // register the broad phase pair filter with the PhysicsWorld
+        res.fetch_mut::<PhysicsWorld<N>>()
+            .collider_world_mut()
+            .register_broad_phase_pair_filter("contact_filters", self.pairs.clone());
+
+        // register reader ids for the PhysicsCollider and OneWayPlatform storages
+        let mut physics_collider_storage: WriteStorage<PhysicsCollider<N>> =
+            SystemData::fetch(&res);
+        self.physics_colliders_reader_id = Some(physics_collider_storage.register_reader());
+        let mut one_way_platform_storage: WriteStorage<OneWayPlatform<N>> = SystemData::fetch(&res);
+        self.one_way_platforms_reader_id = Some(one_way_platform_storage.register_reader());
+    }
+}
@@ -0,0 +1,107 @@
+use std::marker::PhantomData;
+
+use amethyst::ecs::{Join, ReadExpect, Resources, System, SystemData, WriteStorage};
+
+use crate::{
+    contacts::{ContactImpulses, ContactPoint, Contacts, EntityContact},
+    math::RealField,
+    systems::collider_entity,
+    PhysicsWorld,
+};
+
+/// The `ContactsSystem` fills the `Contacts` `Component`s with the contact
+/// pairs of the `PhysicsWorld` after each step, including the impulses
+/// recorded by the `ImpulseRecorder` during the step.
+///
+/// This `System` has to be executed after the `PhysicsStepperSystem`.
+pub struct ContactsSystem<N: RealField> {
+    marker: PhantomData<N>,
+}
+
+impl<N: RealField> Default for ContactsSystem<N> {
+    fn default() -> Self {
+        Self {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'s, N: RealField> System<'s> for ContactsSystem<N> {
+    type SystemData = (
+        ReadExpect<'s, PhysicsWorld<N>>,
+        ReadExpect<'s, ContactImpulses<N>>,
+        WriteStorage<'s, Contacts<N>>,
+    );
+
+    fn run(&mut self, (physics_world, contact_impulses, mut contacts): Self::SystemData) {
+        // reset the contacts of the previous step
+        for contacts in (&mut contacts).join() {
+            contacts.contacts.clear();
+        }
+
+        // iterate over all contact pairs with at least one contact point and add them
+        // to the Contacts of both sides
+        for (handle1, handle2, _, manifold) in physics_world.collider_world().contact_pairs(true) {
+            if manifold.len() == 0 {
+                continue;
+            }
+
+            let (entity1, entity2) = match (
+                collider_entity(&physics_world, handle1),
+                collider_entity(&physics_world, handle2),
+            ) {
+                (Some(entity1), Some(entity2)) => (entity1, entity2),
+                _ => continue,
+            };
+            let impulses = contact_impulses.get(handle1, handle2).unwrap_or_default();
+            let impulse = |i: usize| impulses.get(i).cloned().unwrap_or_else(N::zero);
+
+            if let Some(contacts) = contacts.get_mut(entity1) {
+                contacts.contacts.push(EntityContact {
+                    entity: entity2,
+                    points: manifold
+                        .contacts()
+                        .enumerate()
+                        .map(|(i, tracked)| ContactPoint {
+                            point: tracked.contact.world1,
+                            other_point: tracked.contact.world2,
+                            normal: tracked.contact.normal.into_inner(),
+                            depth: tracked.contact.depth,
+                            impulse: impulse(i),
+                        })
+                        .collect(),
+                });
+            }
+
+            // the contact normal points from the first to the second Collider and has to be
+            // flipped for the second Entity
+            if let Some(contacts) = contacts.get_mut(entity2) {
+                contacts.contacts.push(EntityContact {
+                    entity: entity1,
+                    points: manifold
+                        .contacts()
+                        .enumerate()
+                        .map(|(i, tracked)| ContactPoint {
+                            point: tracked.contact.world2,
+                            other_point: tracked.contact.world1,
+                            normal: -tracked.contact.normal.into_inner(),
+                            depth: tracked.contact.depth,
+                            impulse: impulse(i),
+                        })
+                        .collect(),
+                });
+            }
+        }
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        info!("ContactsSystem.setup");
+        Self::SystemData::setup(res);
+
+        // initialise required resources
+        res.entry::<PhysicsWorld<N>>()
+            .or_insert_with(PhysicsWorld::<N>::new);
+        res.entry::<ContactImpulses<N>>()
+            .or_insert_with(ContactImpulses::<N>::default);
+    }
+}
@@ -1,5 +1,7 @@
+use std::marker::PhantomData;
+
 use amethyst::{
-    ecs::{Join, ReadExpect, ReadStorage, Resources, System, SystemData, Write},
+    ecs::{Entities, Join, ReadExpect, ReadStorage, Resources, System, SystemData, Write},
     renderer::{
         debug_drawing::{DebugLines, DebugLinesParams},
         palette::Srgba,
@@ -8,34 +10,64 @@ use amethyst::{
 
 use crate::{
     collider::{PhysicsCollider, Shape},
+    error::{PhysicsError, PhysicsErrors},
+    math::RealField,
+    systems::to_f32,
     PhysicsWorld,
 };
 
 /// The `DebugSystem`s handles the drawing of `DebugLines` elements for
 /// `PhysicsCollider`s. This visualises the `PhysicsCollider` and enables easier
-/// debugging of collisions.
-#[derive(Default)]
-pub struct DebugSystem;
+/// debugging of collisions. `PhysicsCollider`s without a `Collider` are
+/// skipped and reported as `PhysicsError`s.
+pub struct DebugSystem<N: RealField> {
+    marker: PhantomData<N>,
+}
+
+impl<N: RealField> Default for DebugSystem<N> {
+    fn default() -> Self {
+        Self {
+            marker: PhantomData,
+        }
+    }
+}
 
-impl<'s> System<'s> for DebugSystem {
+impl<'s, N: RealField> System<'s> for DebugSystem<N> {
     type SystemData = (
-        ReadExpect<'s, PhysicsWorld>,
-        ReadStorage<'s, PhysicsCollider>,
+        Entities<'s>,
+        ReadExpect<'s, PhysicsWorld<N>>,
+        ReadStorage<'s, PhysicsCollider<N>>,
         Write<'s, DebugLines>,
+        Write<'s, PhysicsErrors>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (physics_world, physics_colliders, mut debug_lines) = data;
+        let (entities, physics_world, physics_colliders, mut debug_lines, mut physics_errors) =
+            data;
 
         // iterate over PhysicsColliders and their Transforms and draw lines accordingly
-        for physics_collider in (&physics_colliders).join() {
+        for (entity, physics_collider) in (&entities, &physics_colliders).join() {
+            let collider = physics_collider
+                .handle
+                .and_then(|handle| physics_world.collider(handle));
+            if collider.is_none() {
+                physics_errors.single_write(PhysicsError::MissingCollider { entity });
+            }
+
             // fetch the parent for its position
-            if let Some(collider) = physics_world.collider(physics_collider.handle.unwrap()) {
-                // center of the Collider
+            if let Some(collider) = collider {
+                // center of the Collider; 2D Colliders are drawn on the z = 0 plane
+                #[cfg(feature = "dim2")]
                 let (x, y, z) = (
-                    collider.position().translation.vector.x,
-                    collider.position().translation.vector.y,
-                    collider.position().translation.vector.z,
+                    to_f32(collider.position().translation.vector.x),
+                    to_f32(collider.position().translation.vector.y),
+                    0.0,
+                );
+                #[cfg(feature = "dim3")]
+                let (x, y, z) = (
+                    to_f32(collider.position().translation.vector.x),
+                    to_f32(collider.position().translation.vector.y),
+                    to_f32(collider.position().translation.vector.z),
                 );
 
                 // color based on type
@@ -47,8 +79,10 @@ impl<'s> System<'s> for DebugSystem {
 
                 // depending on the Shape we draw the DebugLines differently; right now we only
                 // support Shape::Rectangle
-                match physics_collider.shape {
+                match physics_collider.scaled_shape() {
                     Shape::Rectangle(width, height, _) => {
+                        let (width, height) = (to_f32(width), to_f32(height));
+
                         // draw top line
                         debug_lines.draw_line(
                             [x - width / 2.0, y + height / 2.0, z].into(),
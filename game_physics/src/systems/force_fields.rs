@@ -0,0 +1,232 @@
+use std::marker::PhantomData;
+
+use amethyst::{
+    core::{transform::Transform, Parent},
+    ecs::{
+        storage::ComponentEvent,
+        BitSet,
+        Entities,
+        Join,
+        ReadExpect,
+        ReadStorage,
+        ReaderId,
+        Resources,
+        System,
+        SystemData,
+        WriteExpect,
+        WriteStorage,
+    },
+};
+use nphysics::object::BodyHandle;
+
+use crate::{
+    body::{PhysicsBody, PhysicsBodyHandles},
+    collider::PhysicsColliderHandles,
+    force_field::{
+        ForceField,
+        ForceFieldBody,
+        ForceFieldContext,
+        ForceFieldRegion,
+        PhysicsForceFieldHandles,
+    },
+    math::RealField,
+    systems::world_transform,
+    Gravity,
+    PhysicsWorld,
+    Point,
+};
+
+/// The `ForceFieldsSystem` adds and removes the nphysics `ForceGenerator`s of
+/// `ForceField` `Component`s and computes the forces applied to all
+/// `RigidBody`s inside their regions during the upcoming step.
+///
+/// This `System` has to be executed before the `PhysicsStepperSystem`.
+pub struct ForceFieldsSystem<N: RealField> {
+    force_fields_reader_id: Option<ReaderId<ComponentEvent>>,
+    marker: PhantomData<N>,
+}
+
+impl<N: RealField> Default for ForceFieldsSystem<N> {
+    fn default() -> Self {
+        Self {
+            force_fields_reader_id: None,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'s, N: RealField> System<'s> for ForceFieldsSystem<N> {
+    type SystemData = (
+        Entities<'s>,
+        ReadExpect<'s, Gravity<N>>,
+        ReadExpect<'s, PhysicsBodyHandles>,
+        ReadExpect<'s, PhysicsColliderHandles>,
+        ReadStorage<'s, Parent>,
+        ReadStorage<'s, PhysicsBody<N>>,
+        ReadStorage<'s, Transform>,
+        WriteExpect<'s, PhysicsForceFieldHandles>,
+        WriteExpect<'s, PhysicsWorld<N>>,
+        WriteStorage<'s, ForceField<N>>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            gravity,
+            physics_body_handles,
+            physics_collider_handles,
+            parents,
+            physics_bodies,
+            transforms,
+            mut physics_force_field_handles,
+            mut physics_world,
+            mut force_fields,
+        ) = data;
+
+        // collect inserted and removed ForceField components
+        let mut inserted_force_fields = BitSet::new();
+        let mut removed_force_fields = BitSet::new();
+        for component_event in force_fields
+            .channel()
+            .read(self.force_fields_reader_id.as_mut().unwrap())
+        {
+            match component_event {
+                ComponentEvent::Inserted(id) => {
+                    inserted_force_fields.add(*id);
+                }
+                ComponentEvent::Removed(id) => {
+                    removed_force_fields.add(*id);
+                }
+                _ => {}
+            }
+        }
+
+        // remove the ForceGenerators of removed ForceFields
+        for id in &removed_force_fields {
+            if let Some(handle) = physics_force_field_handles.remove(&id) {
+                physics_world.remove_force_generator(handle);
+                debug!("Removed force field from world with id: {}", id);
+            }
+        }
+
+        // add ForceGenerators for inserted ForceFields
+        for (force_field, id) in (&force_fields, &inserted_force_fields).join() {
+            if let Some(handle) = physics_force_field_handles.remove(&id) {
+                warn!("Removing orphaned force field handle: {:?}", handle);
+                physics_world.remove_force_generator(handle);
+            }
+
+            let handle = physics_world.add_force_generator(force_field.forces.clone());
+            physics_force_field_handles.insert(id, handle);
+            debug!("Inserted force field to world with id: {}", id);
+        }
+
+        // collect the state of all RigidBodies once
+        let bodies = (&entities, &physics_bodies)
+            .join()
+            .filter_map(|(entity, _)| {
+                let handle = *physics_body_handles.get(&entity.id())?;
+                let rigid_body = physics_world.rigid_body(handle)?;
+                let aabb = physics_collider_handles
+                    .get(&entity.id())
+                    .and_then(|handle| physics_world.collider(*handle))
+                    .map(|collider| collider.shape().aabb(collider.position()));
+
+                Some((
+                    handle,
+                    ForceFieldBody {
+                        entity,
+                        center_of_mass: rigid_body.center_of_mass(),
+                        velocity: rigid_body.velocity().linear,
+                        aabb,
+                    },
+                ))
+            })
+            .collect::<Vec<(BodyHandle, ForceFieldBody<N>)>>();
+
+        let delta_time = physics_world.timestep();
+
+        // compute the forces of every ForceField for all RigidBodies inside its region
+        for (entity, force_field, _) in (&entities, &mut force_fields, &transforms).join() {
+            if force_field.generator.is_finished() {
+                force_field.forces.set(Vec::new());
+                continue;
+            }
+
+            let position = match world_transform::<N, _, _>(entity, &parents, &transforms) {
+                Some((isometry, _)) => Point::from(isometry.translation.vector),
+                None => continue,
+            };
+            let field_collider = physics_collider_handles
+                .get(&entity.id())
+                .and_then(|handle| physics_world.collider(*handle));
+
+            let context = ForceFieldContext {
+                position,
+                radius: match force_field.region {
+                    ForceFieldRegion::Radius(radius) => radius,
+                    ForceFieldRegion::Collider => field_collider.map_or(N::zero(), |collider| {
+                        collider
+                            .shape()
+                            .bounding_sphere(collider.position())
+                            .radius()
+                    }),
+                },
+                gravity: *gravity,
+                delta_time,
+            };
+
+            let mut forces = Vec::new();
+            for (handle, body) in &bodies {
+                if body.entity == entity {
+                    continue;
+                }
+
+                let inside = match force_field.region {
+                    ForceFieldRegion::Radius(radius) => {
+                        (body.center_of_mass - context.position).norm() <= radius
+                    }
+                    ForceFieldRegion::Collider => field_collider.map_or(false, |collider| {
+                        collider
+                            .shape()
+                            .as_point_query()
+                            .map_or(false, |point_query| {
+                                point_query
+                                    .contains_point(collider.position(), &body.center_of_mass)
+                            })
+                    }),
+                };
+
+                if inside {
+                    if let Some((force, force_type)) = force_field.generator.force(&context, body) {
+                        forces.push((*handle, force, force_type));
+                    }
+                }
+            }
+
+            force_field.generator.step_finished();
+            force_field.forces.set(forces);
+        }
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        info!("ForceFieldsSystem.setup");
+        Self::SystemData::setup(res);
+
+        // initialise required resources
+        res.entry::<PhysicsWorld<N>>()
+            .or_insert_with(PhysicsWorld::<N>::new);
+        res.entry::<Gravity<N>>()
+            .or_insert_with(Gravity::<N>::zeros);
+        res.entry::<PhysicsBodyHandles>()
+            .or_insert(PhysicsBodyHandles::new());
+        res.entry::<PhysicsColliderHandles>()
+            .or_insert(PhysicsColliderHandles::new());
+        res.entry::<PhysicsForceFieldHandles>()
+            .or_insert(PhysicsForceFieldHandles::new());
+
+        // register reader id for the ForceField storage
+        let mut force_field_storage: WriteStorage<ForceField<N>> = SystemData::fetch(&res);
+        self.force_fields_reader_id = Some(force_field_storage.register_reader());
+    }
+}
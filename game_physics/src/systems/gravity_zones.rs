@@ -0,0 +1,184 @@
+use std::marker::PhantomData;
+
+use amethyst::ecs::{
+    Entities,
+    Join,
+    ReadExpect,
+    ReadStorage,
+    Resources,
+    System,
+    SystemData,
+    Write,
+    WriteExpect,
+};
+use nphysics::object::{Body, BodyHandle, BodyStatus};
+
+use crate::{
+    body::{PhysicsBody, PhysicsBodyHandles},
+    collider::PhysicsColliderHandles,
+    force_field::{Force, ForceFieldForces, ForceType},
+    gravity::{GravityCombine, GravityZone, ResolvedGravities},
+    math::RealField,
+    Gravity,
+    PhysicsWorld,
+    Point,
+    Vector,
+};
+
+/// The `GravityZonesSystem` resolves the gravity of every dynamic `RigidBody`
+/// from the global `Gravity`, the `GravityZone`s it is inside of and its
+/// `gravity_scale`. `RigidBody`s without any deviation from the global
+/// `Gravity` keep using the gravity of the `PhysicsWorld`; for all others the
+/// world gravity is disabled and the resolved gravity is applied as an
+/// acceleration by a nphysics `ForceGenerator`. The deviating gravities are
+/// additionally stored in the `ResolvedGravities`, e.g. to find the up
+/// direction of the `GroundStatesSystem`.
+///
+/// This `System` has to be executed before the `PhysicsStepperSystem` and
+/// after the `SyncBodiesSystem`.
+pub struct GravityZonesSystem<N: RealField> {
+    forces: ForceFieldForces<N>,
+    marker: PhantomData<N>,
+}
+
+impl<N: RealField> Default for GravityZonesSystem<N> {
+    fn default() -> Self {
+        Self {
+            forces: ForceFieldForces::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'s, N: RealField> System<'s> for GravityZonesSystem<N> {
+    type SystemData = (
+        Entities<'s>,
+        ReadExpect<'s, Gravity<N>>,
+        ReadExpect<'s, PhysicsBodyHandles>,
+        ReadExpect<'s, PhysicsColliderHandles>,
+        ReadStorage<'s, GravityZone<N>>,
+        ReadStorage<'s, PhysicsBody<N>>,
+        Write<'s, ResolvedGravities<N>>,
+        WriteExpect<'s, PhysicsWorld<N>>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            gravity,
+            physics_body_handles,
+            physics_collider_handles,
+            gravity_zones,
+            physics_bodies,
+            mut resolved_gravities,
+            mut physics_world,
+        ) = data;
+
+        // collect all GravityZones with a Collider in a deterministic order
+        let mut zones = (&entities, &gravity_zones)
+            .join()
+            .filter_map(|(entity, gravity_zone)| {
+                let handle = *physics_collider_handles.get(&entity.id())?;
+                Some((entity, gravity_zone, handle))
+            })
+            .collect::<Vec<_>>();
+        zones.sort_by_key(|(entity, gravity_zone, _)| (gravity_zone.priority, entity.id()));
+
+        // resolve the gravity of every dynamic RigidBody
+        let resolved = (&entities, &physics_bodies)
+            .join()
+            .filter(|(_, physics_body)| physics_body.body_status == BodyStatus::Dynamic)
+            .filter_map(|(entity, physics_body)| {
+                let handle = *physics_body_handles.get(&entity.id())?;
+                let rigid_body = physics_world.rigid_body(handle)?;
+
+                if !physics_body.gravity_enabled {
+                    return Some((handle, false, None));
+                }
+
+                let center_of_mass = rigid_body.center_of_mass();
+                let mut effective_gravity: Vector<N> = *gravity;
+                let mut in_zone = false;
+                for (zone_entity, gravity_zone, zone_handle) in &zones {
+                    if *zone_entity == entity {
+                        continue;
+                    }
+
+                    let collider = match physics_world.collider(*zone_handle) {
+                        Some(collider) => collider,
+                        None => continue,
+                    };
+                    let inside = collider
+                        .shape()
+                        .as_point_query()
+                        .map_or(false, |point_query| {
+                            point_query.contains_point(collider.position(), &center_of_mass)
+                        });
+                    if !inside {
+                        continue;
+                    }
+
+                    in_zone = true;
+                    let center = Point::from(collider.position().translation.vector);
+                    let zone_gravity = gravity_zone.gravity(&center, &center_of_mass);
+                    match gravity_zone.combine {
+                        GravityCombine::Override => effective_gravity = zone_gravity,
+                        GravityCombine::Additive => effective_gravity += zone_gravity,
+                    }
+                }
+
+                if !in_zone && physics_body.gravity_scale == N::one() {
+                    return Some((handle, true, None, false));
+                }
+
+                // sleeping RigidBodies must not be woken up by their gravity, so it is
+                // only applied to active ones
+                Some((
+                    handle,
+                    false,
+                    Some(effective_gravity * physics_body.gravity_scale),
+                    rigid_body.is_active(),
+                ))
+            })
+            .collect::<Vec<(BodyHandle, bool, Option<Vector<N>>, bool)>>();
+
+        let mut forces = Vec::new();
+        resolved_gravities.clear();
+        for (handle, world_gravity, custom_gravity, active) in resolved {
+            if let Some(rigid_body) = physics_world.rigid_body_mut(handle) {
+                rigid_body.enable_gravity(world_gravity);
+            }
+
+            if let Some(custom_gravity) = custom_gravity {
+                resolved_gravities.insert(handle, custom_gravity);
+                if active {
+                    forces.push((
+                        handle,
+                        Force::linear(custom_gravity),
+                        ForceType::AccelerationChange,
+                    ));
+                }
+            }
+        }
+        self.forces.set(forces);
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        info!("GravityZonesSystem.setup");
+        Self::SystemData::setup(res);
+
+        // initialise required resources
+        res.entry::<PhysicsWorld<N>>()
+            .or_insert_with(PhysicsWorld::<N>::new);
+        res.entry::<Gravity<N>>()
+            .or_insert_with(Gravity::<N>::zeros);
+        res.entry::<PhysicsBodyHandles>()
+            .or_insert(PhysicsBodyHandles::new());
+        res.entry::<PhysicsColliderHandles>()
+            .or_insert(PhysicsColliderHandles::new());
+
+        // add the ForceGenerator applying the resolved gravity
+        res.fetch_mut::<PhysicsWorld<N>>()
+            .add_force_generator(self.forces.clone());
+    }
+}
@@ -0,0 +1,182 @@
+use std::{collections::HashMap, marker::PhantomData};
+
+use amethyst::ecs::{
+    Entities,
+    Entity,
+    Join,
+    Read,
+    ReadExpect,
+    Resources,
+    System,
+    SystemData,
+    Write,
+    WriteStorage,
+};
+use nphysics::object::{Body, ColliderHandle};
+
+use crate::{
+    gravity::ResolvedGravities,
+    ground::{GroundEvent, GroundEventChannel, GroundState},
+    math::RealField,
+    systems::collider_entity,
+    Gravity,
+    PhysicsWorld,
+    Vector,
+};
+
+/// The `GroundStatesSystem` updates the `GroundState` `Component`s from the
+/// contact pairs of the `PhysicsWorld` after each step and publishes a
+/// `GroundEvent` whenever an `Entity` lands or leaves the ground. The up
+/// direction of every `Entity` is opposite to the gravity acting on its
+/// `RigidBody`: the one in the `ResolvedGravities` of the `GravityZonesSystem`
+/// if there is one, the global `Gravity` otherwise. The ground velocity is the
+/// velocity of the ground `RigidBody` at the contact point, including its
+/// rotation.
+///
+/// This `System` has to be executed after the `PhysicsStepperSystem`.
+pub struct GroundStatesSystem<N: RealField> {
+    marker: PhantomData<N>,
+}
+
+impl<N: RealField> Default for GroundStatesSystem<N> {
+    fn default() -> Self {
+        Self {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'s, N: RealField> System<'s> for GroundStatesSystem<N> {
+    type SystemData = (
+        Entities<'s>,
+        ReadExpect<'s, Gravity<N>>,
+        Read<'s, ResolvedGravities<N>>,
+        ReadExpect<'s, PhysicsWorld<N>>,
+        Write<'s, GroundEventChannel>,
+        WriteStorage<'s, GroundState<N>>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            gravity,
+            resolved_gravities,
+            physics_world,
+            mut ground_events,
+            mut ground_states,
+        ) = data;
+
+        // the up direction is opposite to the gravity acting on the RigidBody of a
+        // Collider
+        let up = |handle: ColliderHandle| {
+            let gravity = physics_world
+                .collider(handle)
+                .and_then(|collider| resolved_gravities.get(&collider.body()))
+                .unwrap_or(&*gravity);
+            if gravity.norm() > N::default_epsilon() {
+                -gravity.normalize()
+            } else {
+                Vector::y()
+            }
+        };
+
+        // collect the steepest acceptable ground contact of all Entities with a
+        // GroundState; the normal points away from the ground
+        let mut grounds: HashMap<Entity, (Entity, Vector<N>, Vector<N>, N)> = HashMap::new();
+        for (handle1, handle2, _, manifold) in physics_world.collider_world().contact_pairs(true) {
+            let contact = match manifold.deepest_contact() {
+                Some(tracked) => tracked.contact.clone(),
+                None => continue,
+            };
+
+            let (entity1, entity2) = match (
+                collider_entity(&physics_world, handle1),
+                collider_entity(&physics_world, handle2),
+            ) {
+                (Some(entity1), Some(entity2)) => (entity1, entity2),
+                _ => continue,
+            };
+
+            // the contact normal points from the first to the second Collider
+            let normal = contact.normal.into_inner();
+            for (entity, handle, ground, ground_handle, ground_normal, ground_point) in &[
+                (entity1, handle1, entity2, handle2, -normal, contact.world2),
+                (entity2, handle2, entity1, handle1, normal, contact.world1),
+            ] {
+                let ground_state = match ground_states.get(*entity) {
+                    Some(ground_state) => ground_state,
+                    None => continue,
+                };
+
+                let alignment = ground_normal.dot(&up(*handle));
+                if alignment < ground_state.max_slope.cos() {
+                    continue;
+                }
+
+                let flattest = grounds
+                    .get(entity)
+                    .map_or(true, |(.., flattest)| alignment > *flattest);
+                if flattest {
+                    // the velocity of the ground at the contact point, e.g. of a
+                    // rotating platform
+                    let velocity = physics_world
+                        .collider(*ground_handle)
+                        .and_then(|collider| physics_world.rigid_body(collider.body()))
+                        .map_or(Vector::zeros(), |rigid_body| {
+                            let offset = ground_point - rigid_body.center_of_mass();
+                            rigid_body.velocity().shift(&offset).linear
+                        });
+                    grounds.insert(*entity, (*ground, *ground_normal, velocity, alignment));
+                }
+            }
+        }
+
+        let delta_time = physics_world.timestep();
+        for (entity, ground_state) in (&entities, &mut ground_states).join() {
+            let was_grounded = ground_state.grounded;
+
+            match grounds.remove(&entity) {
+                Some((ground, normal, velocity, _)) => {
+                    ground_state.touching = true;
+                    ground_state.airborne_time = N::zero();
+                    ground_state.entity = Some(ground);
+                    ground_state.normal = Some(normal);
+                    ground_state.velocity = velocity;
+                }
+                None => {
+                    ground_state.touching = false;
+                    ground_state.airborne_time += delta_time;
+                    ground_state.entity = None;
+                    ground_state.normal = None;
+                    ground_state.velocity = Vector::zeros();
+                }
+            }
+
+            ground_state.grounded = ground_state.touching
+                || (was_grounded && ground_state.airborne_time <= ground_state.coyote_time);
+
+            match (was_grounded, ground_state.grounded, ground_state.entity) {
+                (false, true, Some(ground)) => {
+                    debug!("Entity {:?} landed on {:?}", entity, ground);
+                    ground_events.single_write(GroundEvent::Landed { entity, ground });
+                }
+                (true, false, _) => {
+                    debug!("Entity {:?} left the ground", entity);
+                    ground_events.single_write(GroundEvent::Left { entity });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        info!("GroundStatesSystem.setup");
+        Self::SystemData::setup(res);
+
+        // initialise required resources
+        res.entry::<PhysicsWorld<N>>()
+            .or_insert_with(PhysicsWorld::<N>::new);
+        res.entry::<Gravity<N>>()
+            .or_insert_with(Gravity::<N>::zeros);
+    }
+}
@@ -0,0 +1,107 @@
+use std::marker::PhantomData;
+
+use amethyst::ecs::{
+    Entities,
+    Join,
+    ReadExpect,
+    ReadStorage,
+    Resources,
+    System,
+    SystemData,
+    Write,
+    WriteExpect,
+    WriteStorage,
+};
+use nphysics::{math::Velocity, object::Body};
+
+use crate::{
+    body::{PhysicsBody, PhysicsBodyHandles},
+    kinematic::{KinematicPath, KinematicPathEvent, KinematicPathEventChannel},
+    math::{self, RealField},
+    PhysicsWorld,
+    Vector,
+};
+
+/// The `KinematicPathsSystem` advances all `KinematicPath`s by one
+/// `PhysicsWorld` time step and sets the velocity of their `RigidBody`s so that
+/// they arrive at the new position on the path during the following step.
+/// `KinematicPathEvent`s are published for every reached waypoint.
+///
+/// This `System` has to be executed before the `PhysicsStepperSystem`.
+pub struct KinematicPathsSystem<N: RealField> {
+    marker: PhantomData<N>,
+}
+
+impl<N: RealField> Default for KinematicPathsSystem<N> {
+    fn default() -> Self {
+        Self {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'s, N: RealField> System<'s> for KinematicPathsSystem<N> {
+    type SystemData = (
+        Entities<'s>,
+        ReadExpect<'s, PhysicsBodyHandles>,
+        ReadStorage<'s, PhysicsBody<N>>,
+        Write<'s, KinematicPathEventChannel>,
+        WriteExpect<'s, PhysicsWorld<N>>,
+        WriteStorage<'s, KinematicPath<N>>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            physics_body_handles,
+            physics_bodies,
+            mut kinematic_path_events,
+            mut physics_world,
+            mut kinematic_paths,
+        ) = data;
+
+        let delta_time = physics_world.timestep();
+
+        for (entity, kinematic_path, _) in (&entities, &mut kinematic_paths, &physics_bodies).join()
+        {
+            let rigid_body = match physics_body_handles
+                .get(&entity.id())
+                .and_then(|handle| physics_world.rigid_body_mut(*handle))
+            {
+                Some(rigid_body) => rigid_body,
+                None => continue,
+            };
+
+            // advance the path and publish all reached waypoints
+            let was_finished = kinematic_path.is_finished();
+            for waypoint in kinematic_path.advance(delta_time) {
+                debug!("Entity {:?} reached waypoint {}", entity, waypoint);
+                kinematic_path_events
+                    .single_write(KinematicPathEvent::WaypointReached { entity, waypoint });
+            }
+            if !was_finished && kinematic_path.is_finished() {
+                kinematic_path_events.single_write(KinematicPathEvent::Finished { entity });
+            }
+
+            // move towards the new position on the path via the velocity; this also
+            // compensates any drift of the RigidBody from the path
+            let velocity = match kinematic_path.position() {
+                Some(target) => (target - rigid_body.position().translation.vector) / delta_time,
+                None => Vector::zeros(),
+            };
+            rigid_body.set_velocity(Velocity::new(velocity, math::zero()));
+            rigid_body.activate();
+        }
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        info!("KinematicPathsSystem.setup");
+        Self::SystemData::setup(res);
+
+        // initialise required resources
+        res.entry::<PhysicsWorld<N>>()
+            .or_insert_with(PhysicsWorld::<N>::new);
+        res.entry::<PhysicsBodyHandles>()
+            .or_insert(PhysicsBodyHandles::new());
+    }
+}
@@ -0,0 +1,358 @@
+use std::{collections::HashMap, marker::PhantomData};
+
+use amethyst::{
+    core::Parent,
+    ecs::{
+        storage::ComponentEvent,
+        world::Index,
+        BitSet,
+        Entities,
+        Join,
+        ReadExpect,
+        ReadStorage,
+        ReaderId,
+        Resources,
+        System,
+        SystemData,
+        WriteExpect,
+        WriteStorage,
+    },
+};
+use ncollide::shape::Shape;
+use nphysics::volumetric::Volumetric;
+
+use crate::{
+    body::{PhysicsBody, PhysicsBodyHandles},
+    collider::PhysicsCollider,
+    math::{self, RealField},
+    systems::{ancestors, body_entity, component_changes},
+    AngularInertia,
+    Isometry,
+    PhysicsWorld,
+    Point,
+    Vector,
+};
+
+/// The `MassProperties` accumulate the mass, centre of mass and angular
+/// inertia of all `PhysicsCollider`s attached to a single `PhysicsBody`.
+struct MassProperties<N: RealField> {
+    /// The individual parts as (mass, local centre of mass, local angular
+    /// inertia around the centre of mass).
+    parts: Vec<(N, Point<N>, AngularInertia<N>)>,
+}
+
+impl<N: RealField> MassProperties<N> {
+    fn new() -> Self {
+        Self { parts: Vec::new() }
+    }
+
+    /// Adds the mass properties of a `PhysicsCollider` positioned at the given
+    /// `Isometry` relative to the `PhysicsBody`.
+    fn add(&mut self, physics_collider: &PhysicsCollider<N>, position: &Isometry<N>) {
+        let shape_handle = physics_collider.shape_handle();
+        let shape: &dyn Shape<N> = &*shape_handle;
+        let (mass, center_of_mass, angular_inertia) =
+            shape.mass_properties(physics_collider.density);
+
+        self.parts.push((
+            mass,
+            position * center_of_mass,
+            rotate_inertia(&angular_inertia, position),
+        ));
+    }
+
+    /// Combines all parts into the total mass, the common centre of mass and
+    /// the angular inertia around said centre of mass (parallel axis
+    /// theorem). Returns `None` if the total mass is zero.
+    fn combine(&self) -> Option<(N, Point<N>, AngularInertia<N>)> {
+        let mass = self
+            .parts
+            .iter()
+            .fold(N::zero(), |total, (mass, ..)| total + *mass);
+        if mass <= N::zero() {
+            return None;
+        }
+
+        let center_of_mass = Point::from(
+            self.parts
+                .iter()
+                .fold(Vector::zeros(), |total, (part_mass, part_center, _)| {
+                    total + part_center.coords * *part_mass
+                })
+                / mass,
+        );
+
+        let angular_inertia = self.parts.iter().fold(
+            math::zero(),
+            |total: AngularInertia<N>, (part_mass, part_center, part_inertia)| {
+                total + shift_inertia(part_inertia, *part_mass, &(part_center - center_of_mass))
+            },
+        );
+
+        Some((mass, center_of_mass, angular_inertia))
+    }
+}
+
+/// Rotates a local angular inertia into the frame of the `PhysicsBody`. In 2D
+/// the angular inertia is invariant to rotations.
+#[allow(unused_variables)]
+fn rotate_inertia<N: RealField>(
+    angular_inertia: &AngularInertia<N>,
+    position: &Isometry<N>,
+) -> AngularInertia<N> {
+    #[cfg(feature = "dim2")]
+    {
+        *angular_inertia
+    }
+    #[cfg(feature = "dim3")]
+    {
+        let rotation = position.rotation.to_rotation_matrix();
+        rotation.matrix() * angular_inertia * rotation.matrix().transpose()
+    }
+}
+
+/// Shifts an angular inertia around a centre of mass by the given offset
+/// according to the parallel axis theorem.
+fn shift_inertia<N: RealField>(
+    angular_inertia: &AngularInertia<N>,
+    mass: N,
+    offset: &Vector<N>,
+) -> AngularInertia<N> {
+    #[cfg(feature = "dim2")]
+    {
+        angular_inertia + AngularInertia::new(mass * offset.norm_squared())
+    }
+    #[cfg(feature = "dim3")]
+    {
+        angular_inertia
+            + (math::Matrix3::identity() * offset.norm_squared() - offset * offset.transpose())
+                * mass
+    }
+}
+
+/// The `MassPropertiesSystem` derives the mass, centre of mass and angular
+/// inertia of all `PhysicsBody`s with `mass_from_colliders` enabled from the
+/// shapes and densities of their attached `PhysicsCollider`s. This includes
+/// `PhysicsCollider`s of descendant `Entity`s via `Parent`. Sensor
+/// `PhysicsCollider`s are ignored.
+///
+/// The mass properties are only recomputed for the `PhysicsBody`s affected by
+/// the `ComponentEvent`s of a frame: modified `PhysicsBody`s themselves and
+/// the previous and current owners of inserted, modified or removed
+/// `PhysicsCollider`s. Inserted or removed `PhysicsBody`s and changed
+/// `Parent`s additionally re-resolve the owners of all `PhysicsCollider`s
+/// below them in the `Parent` hierarchy.
+pub struct MassPropertiesSystem<N: RealField> {
+    physics_bodies_reader_id: Option<ReaderId<ComponentEvent>>,
+    physics_colliders_reader_id: Option<ReaderId<ComponentEvent>>,
+    parents_reader_id: Option<ReaderId<ComponentEvent>>,
+    owners: HashMap<Index, Index>,
+    colliders: HashMap<Index, BitSet>,
+    marker: PhantomData<N>,
+}
+
+impl<N: RealField> Default for MassPropertiesSystem<N> {
+    fn default() -> Self {
+        Self {
+            physics_bodies_reader_id: None,
+            physics_colliders_reader_id: None,
+            parents_reader_id: None,
+            owners: HashMap::new(),
+            colliders: HashMap::new(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<N: RealField> MassPropertiesSystem<N> {
+    /// Stores the new owner of the `PhysicsCollider` with the given id and
+    /// adds both the previous and the new owner to the affected bodies.
+    fn set_owner(&mut self, id: Index, owner: Option<Index>, affected: &mut BitSet) {
+        if let Some(previous) = self.owners.remove(&id) {
+            affected.add(previous);
+            if let Some(colliders) = self.colliders.get_mut(&previous) {
+                colliders.remove(id);
+            }
+        }
+        if let Some(owner) = owner {
+            affected.add(owner);
+            self.owners.insert(id, owner);
+            self.colliders
+                .entry(owner)
+                .or_insert_with(BitSet::new)
+                .add(id);
+        }
+    }
+}
+
+impl<'s, N: RealField> System<'s> for MassPropertiesSystem<N> {
+    type SystemData = (
+        Entities<'s>,
+        ReadExpect<'s, PhysicsBodyHandles>,
+        ReadStorage<'s, Parent>,
+        ReadStorage<'s, PhysicsBody<N>>,
+        ReadStorage<'s, PhysicsCollider<N>>,
+        WriteExpect<'s, PhysicsWorld<N>>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            physics_body_handles,
+            parents,
+            physics_bodies,
+            physics_colliders,
+            mut physics_world,
+        ) = data;
+
+        // read all ComponentEvents of this frame at once
+        let body_changes = component_changes(
+            &physics_bodies,
+            self.physics_bodies_reader_id.as_mut().unwrap(),
+        );
+        let collider_changes = component_changes(
+            &physics_colliders,
+            self.physics_colliders_reader_id.as_mut().unwrap(),
+        );
+        let parent_changes = component_changes(&parents, self.parents_reader_id.as_mut().unwrap());
+
+        // changed PhysicsBody components are affected themselves
+        let mut affected = BitSet::new();
+        affected |= &body_changes.inserted;
+        affected |= &body_changes.modified;
+        affected |= &body_changes.removed;
+
+        // changed PhysicsCollider components have to re-resolve their owner
+        let mut changed_colliders = BitSet::new();
+        changed_colliders |= &collider_changes.inserted;
+        changed_colliders |= &collider_changes.modified;
+        changed_colliders |= &collider_changes.removed;
+
+        // inserted or removed RigidBodies and changed Parents change the owners of all
+        // PhysicsCollider components below them in the Parent hierarchy
+        let mut changed_hierarchy = BitSet::new();
+        changed_hierarchy |= &body_changes.inserted;
+        changed_hierarchy |= &body_changes.removed;
+        changed_hierarchy |= &parent_changes.inserted;
+        changed_hierarchy |= &parent_changes.modified;
+        changed_hierarchy |= &parent_changes.removed;
+        if !changed_hierarchy.is_empty() {
+            for (entity, _) in (&entities, &physics_colliders).join() {
+                if ancestors(entity, &parents)
+                    .any(|ancestor| changed_hierarchy.contains(ancestor.id()))
+                {
+                    changed_colliders.add(entity.id());
+                }
+            }
+        }
+
+        if affected.is_empty() && changed_colliders.is_empty() {
+            return;
+        }
+
+        // resolve the owners of all changed PhysicsCollider components; the owner is
+        // the closest Entity with a RigidBody up the Parent hierarchy
+        for id in (&changed_colliders).join() {
+            let entity = entities.entity(id);
+            let owner = if entities.is_alive(entity) && physics_colliders.contains(entity) {
+                body_entity(entity, &parents, &physics_body_handles).map(|owner| owner.id())
+            } else {
+                None
+            };
+            self.set_owner(id, owner, &mut affected);
+        }
+
+        // apply the derived mass properties of all affected PhysicsBody components;
+        // PhysicsBodies without valid PhysicsColliders fall back to their explicit
+        // values
+        for (entity, physics_body, _) in (&entities, &physics_bodies, &affected).join() {
+            if !physics_body.mass_from_colliders {
+                continue;
+            }
+
+            let rigid_body = match physics_body_handles
+                .get(&entity.id())
+                .and_then(|handle| physics_world.rigid_body_mut(*handle))
+            {
+                Some(rigid_body) => rigid_body,
+                None => continue,
+            };
+
+            // accumulate the mass properties of all non-sensor PhysicsColliders owned by
+            // the PhysicsBody; pending PhysicsColliders have not been placed relative to
+            // their owner yet
+            let mut mass_properties = MassProperties::new();
+            if let Some(colliders) = self.colliders.get(&entity.id()) {
+                for id in colliders.join() {
+                    let physics_collider = match physics_colliders.get(entities.entity(id)) {
+                        Some(physics_collider) => physics_collider,
+                        None => continue,
+                    };
+                    if physics_collider.sensor || physics_collider.handle.is_none() {
+                        continue;
+                    }
+                    mass_properties.add(physics_collider, &physics_collider.position_wrt_body);
+                }
+            }
+
+            let (mass, local_center_of_mass, angular_inertia) = match mass_properties.combine() {
+                Some(combined) => combined,
+                None => {
+                    debug!(
+                        "No mass from colliders for {:?}, using explicit values",
+                        entity
+                    );
+                    (
+                        physics_body.mass,
+                        physics_body.local_center_of_mass,
+                        physics_body.angular_inertia,
+                    )
+                }
+            };
+
+            rigid_body.set_mass(mass);
+            rigid_body.set_local_center_of_mass(local_center_of_mass);
+            rigid_body.set_angular_inertia(angular_inertia);
+
+            trace!(
+                "Updated mass properties of {:?}: mass: {}, local_center_of_mass: {}, \
+                 angular_inertia: {}",
+                entity,
+                mass,
+                local_center_of_mass,
+                angular_inertia
+            );
+        }
+
+        // forget the PhysicsColliders of removed owners
+        for id in (&body_changes.removed).join() {
+            if !physics_bodies.contains(entities.entity(id)) {
+                self.colliders.remove(&id);
+            }
+        }
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        info!("MassPropertiesSystem.setup");
+        Self::SystemData::setup(res);
+
+        // initialise required resources
+        res.entry::<PhysicsWorld<N>>()
+            .or_insert_with(PhysicsWorld::<N>::new);
+        res.entry::<PhysicsBodyHandles>()
+            .or_insert(PhysicsBodyHandles::new());
+
+        // register reader id for the PhysicsBody storage
+        let mut physics_body_storage: WriteStorage<PhysicsBody<N>> = SystemData::fetch(&res);
+        self.physics_bodies_reader_id = Some(physics_body_storage.register_reader());
+
+        // register reader id for the PhysicsCollider storage
+        let mut physics_collider_storage: WriteStorage<PhysicsCollider<N>> =
+            SystemData::fetch(&res);
+        self.physics_colliders_reader_id = Some(physics_collider_storage.register_reader());
+
+        // register reader id for the Parent storage
+        let mut parent_storage: WriteStorage<Parent> = SystemData::fetch(&res);
+        self.parents_reader_id = Some(parent_storage.register_reader());
+    }
+}
@@ -1,12 +1,14 @@
-use core::ops::Deref;
+use core::{marker::PhantomData, ops::Deref};
+use std::{path::PathBuf, sync::Arc, time::Duration};
 
 use amethyst::{
-    core::bundle::SystemBundle,
+    core::{bundle::SystemBundle, Float, Parent, Transform},
     ecs::{
         storage::{ComponentEvent, MaskedStorage},
         BitSet,
         Component,
         DispatcherBuilder,
+        Entity,
         ReaderId,
         Storage,
         Tracked,
@@ -14,148 +16,371 @@ use amethyst::{
     error::Error,
 };
 
+use crate::Vector;
+use nphysics::{math::Translation, object::ColliderHandle};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    body::PhysicsBodyHandles,
+    contact_filter::ContactFilter,
+    layers::CollisionLayers,
+    materials::PhysicsMaterials,
+    math::{self, RealField},
+    Isometry,
+    PhysicsWorld,
+};
+
 use self::{
-    body::{
-        add_rigid_bodies::AddRigidBodiesSystem,
-        remove_rigid_bodies::RemoveRigidBodiesSystem,
-        update_rigid_bodies::UpdateRigidBodiesSystems,
-    },
-    collider::{
-        add_colliders::AddCollidersSystem,
-        remove_colliders::RemoveCollidersSystem,
-        update_colliders::UpdateCollidersSystems,
-    },
+    auto_colliders::AutoCollidersSystem,
+    ccd::CcdSystem,
+    contact_filters::ContactFiltersSystem,
+    contacts::ContactsSystem,
     debug::DebugSystem,
+    force_fields::ForceFieldsSystem,
+    gravity_zones::GravityZonesSystem,
+    ground_states::GroundStatesSystem,
+    kinematic_paths::KinematicPathsSystem,
+    mass_properties::MassPropertiesSystem,
     physics_stepper::PhysicsStepperSystem,
+    reload_materials::{MaterialsLoader, ReloadMaterialsSystem},
+    sleep_requests::SleepRequestsSystem,
+    sleep_states::SleepStatesSystem,
+    sync_bodies::SyncBodiesSystem,
+    sync_colliders::SyncCollidersSystem,
     sync_gravity::SyncGravitySystem,
     sync_positions::SyncPositionsSystem,
+    tile_grids::TileGridsSystem,
+    trigger_volumes::TriggerVolumesSystem,
+    world_hash::PhysicsWorldHashSystem,
 };
 
-mod body;
-mod collider;
+mod auto_colliders;
+mod ccd;
+mod contact_filters;
+mod contacts;
 mod debug;
+mod force_fields;
+mod gravity_zones;
+mod ground_states;
+mod kinematic_paths;
+mod mass_properties;
 mod physics_stepper;
+mod reload_materials;
+mod sleep_requests;
+mod sleep_states;
+mod sync_bodies;
+mod sync_colliders;
 mod sync_gravity;
 mod sync_positions;
+mod tile_grids;
+mod trigger_volumes;
+mod world_hash;
 
-/// Bundle containing all `System`s relevant to the game physics.
-#[derive(Default)]
-pub struct PhysicsBundle {
+/// Bundle containing all `System`s relevant to the game physics. The type
+/// parameter `N` defines the scalar type used by the `PhysicsWorld` and all
+/// physics `Component`s; it defaults to `f32`.
+pub struct PhysicsBundle<N: RealField = f32> {
     debug_lines: bool,
+    collision_layers: Option<CollisionLayers>,
+    materials: Option<(PathBuf, MaterialsLoader<N>)>,
+    contact_filter: Option<Arc<dyn ContactFilter<N>>>,
+    stats_log_interval: Option<Duration>,
+    deterministic: bool,
+    marker: PhantomData<N>,
 }
 
-impl<'a, 'b> SystemBundle<'a, 'b> for PhysicsBundle {
+impl<N: RealField> Default for PhysicsBundle<N> {
+    fn default() -> Self {
+        Self {
+            debug_lines: false,
+            collision_layers: None,
+            materials: None,
+            contact_filter: None,
+            stats_log_interval: None,
+            deterministic: false,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, 'b, N: RealField> SystemBundle<'a, 'b> for PhysicsBundle<N> {
     fn build(self, dispatcher: &mut DispatcherBuilder) -> Result<(), Error> {
         // synchronise PhysicsBody components with the PhysicsWorld
         dispatcher.add(
-            AddRigidBodiesSystem::default(),
-            "add_rigid_bodies_system",
+            SyncBodiesSystem::<N>::new(self.deterministic),
+            "sync_bodies_system",
             &[],
         );
+
+        // derive PhysicsColliders from SpriteRenders of AutoColliders
         dispatcher.add(
-            UpdateRigidBodiesSystems::default(),
-            "update_rigid_bodies_system",
-            &["add_rigid_bodies_system"],
-        );
-        dispatcher.add(
-            RemoveRigidBodiesSystem::default(),
-            "remove_rigid_bodies_system",
-            &["add_rigid_bodies_system"],
+            AutoCollidersSystem::<N>::default(),
+            "auto_colliders_system",
+            &[],
         );
 
+        // generate PhysicsColliders for TileGrids
+        dispatcher.add(TileGridsSystem::<N>::default(), "tile_grids_system", &[]);
+
+        // load and hot-reload the PhysicsMaterials on demand
+        let mut sync_colliders_dependencies = vec![
+            "sync_bodies_system",
+            "auto_colliders_system",
+            "tile_grids_system",
+        ];
+        if let Some((path, loader)) = self.materials {
+            dispatcher.add(
+                ReloadMaterialsSystem::<N>::new(path, loader, !self.deterministic),
+                "reload_materials_system",
+                &[],
+            );
+            sync_colliders_dependencies.push("reload_materials_system");
+        }
+
         // synchronise PhysicsCollider components with the PhysicsWorld
         dispatcher.add(
-            AddCollidersSystem::default(),
-            "add_colliders_system",
-            &["add_rigid_bodies_system"],
-        );
-        dispatcher.add(
-            UpdateCollidersSystems::default(),
-            "update_colliders_system",
-            &["add_colliders_system"],
+            SyncCollidersSystem::<N>::new(self.collision_layers, self.deterministic),
+            "sync_colliders_system",
+            &sync_colliders_dependencies,
         );
+
+        // derive mass properties from PhysicsColliders on demand
         dispatcher.add(
-            RemoveCollidersSystem::default(),
-            "remove_colliders_system",
-            &["add_colliders_system"],
+            MassPropertiesSystem::<N>::default(),
+            "mass_properties_system",
+            &["sync_bodies_system", "sync_colliders_system"],
         );
 
         // synchronise Gravity with the PhysicsWorld
-        dispatcher.add(SyncGravitySystem::default(), "sync_gravity_system", &[]);
+        dispatcher.add(
+            SyncGravitySystem::<N>::default(),
+            "sync_gravity_system",
+            &[],
+        );
 
         // enable DebugSystem on demand
         if self.debug_lines {
             dispatcher.add(
-                DebugSystem::default(),
+                DebugSystem::<N>::default(),
                 "debug_system",
-                &[
-                    "add_rigid_bodies_system",
-                    "update_rigid_bodies_system",
-                    "remove_rigid_bodies_system",
-                    "add_colliders_system",
-                    "update_colliders_system",
-                    "remove_colliders_system",
-                ],
+                &["sync_bodies_system", "sync_colliders_system"],
             );
         }
 
+        // move kinematic RigidBodies along their KinematicPaths
+        dispatcher.add(
+            KinematicPathsSystem::<N>::default(),
+            "kinematic_paths_system",
+            &["sync_bodies_system"],
+        );
+
+        // wake up or put to sleep RigidBodies on request
+        dispatcher.add(
+            SleepRequestsSystem::<N>::default(),
+            "sleep_requests_system",
+            &["sync_bodies_system", "sync_colliders_system"],
+        );
+
+        // compute the forces of ForceFields for the upcoming step
+        dispatcher.add(
+            ForceFieldsSystem::<N>::default(),
+            "force_fields_system",
+            &[
+                "sync_bodies_system",
+                "sync_colliders_system",
+                "sync_gravity_system",
+            ],
+        );
+
+        // resolve per body gravity from GravityZones and gravity scales
+        dispatcher.add(
+            GravityZonesSystem::<N>::default(),
+            "gravity_zones_system",
+            &[
+                "sync_bodies_system",
+                "sync_colliders_system",
+                "sync_gravity_system",
+            ],
+        );
+
+        // filter contacts of OneWayPlatforms and the user provided ContactFilter
+        dispatcher.add(
+            ContactFiltersSystem::<N>::new(self.contact_filter),
+            "contact_filters_system",
+            &["sync_colliders_system"],
+        );
+
+        // prevent fast RigidBodies from tunnelling through thin Colliders
+        dispatcher.add(
+            CcdSystem::<N>::default(),
+            "ccd_system",
+            &[
+                "sync_bodies_system",
+                "sync_colliders_system",
+                "mass_properties_system",
+            ],
+        );
+
         // progress the PhysicsWorld
         dispatcher.add(
-            PhysicsStepperSystem::default(),
+            PhysicsStepperSystem::<N>::new(self.stats_log_interval),
             "physics_stepper_system",
             &[
-                "add_rigid_bodies_system",
-                "update_rigid_bodies_system",
-                "remove_rigid_bodies_system",
-                "add_colliders_system",
-                "update_colliders_system",
-                "remove_colliders_system",
+                "sync_bodies_system",
+                "sync_colliders_system",
+                "mass_properties_system",
+                "kinematic_paths_system",
+                "sleep_requests_system",
+                "force_fields_system",
+                "gravity_zones_system",
+                "contact_filters_system",
+                "ccd_system",
                 "sync_gravity_system",
             ],
         );
 
         // synchronise updated position from PhysicsWorld with Amethyst
         dispatcher.add(
-            SyncPositionsSystem::default(),
+            SyncPositionsSystem::<N>::default(),
             "sync_positions_system",
             &["physics_stepper_system"],
         );
 
+        // track Entities overlapping TriggerVolumes based on the last step
+        dispatcher.add(
+            TriggerVolumesSystem::<N>::default(),
+            "trigger_volumes_system",
+            &["physics_stepper_system"],
+        );
+
+        // fill opt-in Contacts components based on the last step
+        dispatcher.add(
+            ContactsSystem::<N>::default(),
+            "contacts_system",
+            &["physics_stepper_system"],
+        );
+
+        // update opt-in GroundState components based on the last step
+        dispatcher.add(
+            GroundStatesSystem::<N>::default(),
+            "ground_states_system",
+            &["physics_stepper_system"],
+        );
+
+        // track sleep state transitions of the last step
+        dispatcher.add(
+            SleepStatesSystem::<N>::default(),
+            "sleep_states_system",
+            &["physics_stepper_system"],
+        );
+
+        // fingerprint the RigidBody states of the last step in deterministic mode
+        if self.deterministic {
+            dispatcher.add(
+                PhysicsWorldHashSystem::<N>::default(),
+                "physics_world_hash_system",
+                &["physics_stepper_system"],
+            );
+        }
+
         Ok(())
     }
 }
 
-impl PhysicsBundle {
+impl<N: RealField> PhysicsBundle<N> {
     /// Enables the `DebugSystem` which draws `DebugLines` around
     /// `PhysicsCollider` shapes.
     pub fn with_debug_lines(mut self) -> Self {
         self.debug_lines = true;
         self
     }
+
+    /// Inserts the given `CollisionLayers` `Resource` used to resolve named
+    /// `PhysicsCollider` layers.
+    pub fn with_collision_layers(mut self, collision_layers: CollisionLayers) -> Self {
+        self.collision_layers = Some(collision_layers);
+        self
+    }
+
+    /// Sets the `ContactFilter` deciding whether a pair of `PhysicsCollider`s
+    /// coming into contact should be ignored.
+    pub fn with_contact_filter<F: ContactFilter<N> + 'static>(mut self, contact_filter: F) -> Self {
+        self.contact_filter = Some(Arc::new(contact_filter));
+        self
+    }
+
+    /// Logs a summary of the `PhysicsStats` `Resource` at most once per given
+    /// interval.
+    pub fn with_stats_log(mut self, interval: Duration) -> Self {
+        self.stats_log_interval = Some(interval);
+        self
+    }
+
+    /// Enables the deterministic mode required for lockstep multiplayer and
+    /// replays: the same sequence of commands produces a bit-identical
+    /// simulation on the same platform. In deterministic mode
+    /// - all synchronised batches are applied in the order of their `Entity`
+    /// - the `PhysicsMaterials` are not hot-reloaded, as that depends on the
+    ///   wall-clock
+    /// - the `PhysicsWorldHash` `Resource` is updated after each step
+    ///
+    /// The timings of the `PhysicsStats` are still measured, but never affect
+    /// the simulation.
+    pub fn deterministic(mut self) -> Self {
+        self.deterministic = true;
+        self
+    }
 }
 
-/// Iterated over the `ComponentEvent::Inserted`s of a given, tracked `Storage`
-/// and returns the results in a `BitSet`.
-pub(crate) fn inserted_components<T, D>(
+impl<N> PhysicsBundle<N>
+where
+    N: RealField + DeserializeOwned,
+{
+    /// Loads the `PhysicsMaterials` `Resource` from the given RON file and
+    /// reloads it whenever the file changes.
+    pub fn with_materials_from_file<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.materials = Some((
+            path.into(),
+            PhysicsMaterials::<N>::from_file as MaterialsLoader<N>,
+        ));
+        self
+    }
+}
+
+/// The `ComponentChanges` contain the ids of all inserted, modified and
+/// removed `Component`s of a tracked `Storage` since the last read. An id may
+/// be contained in multiple sets, e.g. if a `Component` was removed and
+/// inserted again within the same frame.
+pub(crate) struct ComponentChanges {
+    pub(crate) inserted: BitSet,
+    pub(crate) modified: BitSet,
+    pub(crate) removed: BitSet,
+}
+
+/// Reads all `ComponentEvent`s of a given, tracked `Storage` at once and sorts
+/// them into `ComponentChanges`.
+pub(crate) fn component_changes<T, D>(
     tracked_storage: &Storage<T, D>,
     reader_id: &mut ReaderId<ComponentEvent>,
-) -> BitSet
+) -> ComponentChanges
 where
     T: Component,
     T::Storage: Tracked,
     D: Deref<Target = MaskedStorage<T>>,
 {
-    let mut inserted = BitSet::new();
+    let mut changes = ComponentChanges {
+        inserted: BitSet::new(),
+        modified: BitSet::new(),
+        removed: BitSet::new(),
+    };
     for component_event in tracked_storage.channel().read(reader_id) {
         match component_event {
-            ComponentEvent::Inserted(id) => {
-                debug!("Got Inserted event with id: {}", id);
-                inserted.add(*id);
-            }
-            _ => {}
-        }
+            ComponentEvent::Inserted(id) => changes.inserted.add(*id),
+            ComponentEvent::Modified(id) => changes.modified.add(*id),
+            ComponentEvent::Removed(id) => changes.removed.add(*id),
+        };
     }
-    inserted
+    changes
 }
 
 /// Iterated over the `ComponentEvent::Modified`s of a given, tracked `Storage`
@@ -171,37 +396,266 @@ where
 {
     let mut modified = BitSet::new();
     for component_event in tracked_storage.channel().read(reader_id) {
-        match component_event {
-            ComponentEvent::Modified(id) => {
-                debug!("Got Modified event with id: {}", id);
-                modified.add(*id);
-            }
-            _ => {}
+        if let ComponentEvent::Modified(id) = component_event {
+            modified.add(*id);
         }
     }
     modified
 }
 
-/// Iterated over the `ComponentEvent::Removed`s of a given, tracked `Storage`
-/// and returns the results in a `BitSet`.
-pub(crate) fn removed_components<T, D>(
-    tracked_storage: &Storage<T, D>,
-    reader_id: &mut ReaderId<ComponentEvent>,
-) -> BitSet
+/// Returns the `Entity` that was stored as user data of the `Collider` with
+/// the given `ColliderHandle` when it was created by the `SyncCollidersSystem`.
+pub(crate) fn collider_entity<N: RealField>(
+    physics_world: &PhysicsWorld<N>,
+    handle: ColliderHandle,
+) -> Option<Entity> {
+    physics_world
+        .collider(handle)
+        .and_then(|collider| collider.user_data())
+        .and_then(|user_data| user_data.downcast_ref::<Entity>())
+        .cloned()
+}
+
+/// Converts an Amethyst `Float` into the scalar type `N` of the
+/// `PhysicsWorld`.
+pub(crate) fn from_float<N: RealField>(value: Float) -> N {
+    math::convert(value.as_f64())
+}
+
+/// Converts a scalar of type `N` from the `PhysicsWorld` into an Amethyst
+/// `Float`.
+pub(crate) fn to_float<N: RealField>(value: N) -> Float {
+    Float::from(math::try_convert::<N, f64>(value).unwrap_or(0.0))
+}
+
+/// Converts a scalar of type `N` from the `PhysicsWorld` into an `f32`, e.g.
+/// for rendering purposes.
+pub(crate) fn to_f32<N: RealField>(value: N) -> f32 {
+    math::try_convert::<N, f64>(value).unwrap_or(0.0) as f32
+}
+
+/// Converts the position of a `Transform` into an `Isometry` for the
+/// `PhysicsWorld`. In 3D the translation and the rotation are taken into
+/// account; in 2D the translation along the x and y axes and the rotation
+/// around the z axis are used.
+pub(crate) fn transform_isometry<N: RealField>(transform: &Transform) -> Isometry<N> {
+    #[cfg(feature = "dim2")]
+    {
+        Isometry::new(
+            Vector::new(
+                from_float(transform.translation().x),
+                from_float(transform.translation().y),
+            ),
+            from_float(transform.rotation().euler_angles().2),
+        )
+    }
+    #[cfg(feature = "dim3")]
+    {
+        let rotation = transform.rotation().quaternion().coords;
+        Isometry::from_parts(
+            Translation::new(
+                from_float(transform.translation().x),
+                from_float(transform.translation().y),
+                from_float(transform.translation().z),
+            ),
+            math::UnitQuaternion::new_normalize(math::Quaternion::new(
+                from_float(rotation.w),
+                from_float(rotation.x),
+                from_float(rotation.y),
+                from_float(rotation.z),
+            )),
+        )
+    }
+}
+
+/// Converts the scale of a `Transform` into a `Vector` for the
+/// `PhysicsWorld`. In 2D the scale along the z axis is ignored.
+pub(crate) fn transform_scale<N: RealField>(transform: &Transform) -> Vector<N> {
+    #[cfg(feature = "dim2")]
+    {
+        Vector::new(
+            from_float(transform.scale().x),
+            from_float(transform.scale().y),
+        )
+    }
+    #[cfg(feature = "dim3")]
+    {
+        Vector::new(
+            from_float(transform.scale().x),
+            from_float(transform.scale().y),
+            from_float(transform.scale().z),
+        )
+    }
+}
+
+/// Returns the `Isometry` with its translation multiplied by the given scale.
+pub(crate) fn scale_isometry<N: RealField>(
+    isometry: &Isometry<N>,
+    scale: &Vector<N>,
+) -> Isometry<N> {
+    Isometry::from_parts(
+        Translation::from(isometry.translation.vector.component_mul(scale)),
+        isometry.rotation,
+    )
+}
+
+/// Resolves the world space `Isometry` and scale of an `Entity` by composing
+/// the translations, rotations and scales of the `Transform`s of its complete
+/// `Parent` hierarchy. Ancestors without a `Transform` are skipped; `None` is
+/// returned if the `Entity` itself has no `Transform`. Non-uniform scales of
+/// rotated ancestors are only approximated, as the result is no longer an
+/// `Isometry`.
+pub(crate) fn world_transform<N, P, T>(
+    entity: Entity,
+    parents: &Storage<Parent, P>,
+    transforms: &Storage<Transform, T>,
+) -> Option<(Isometry<N>, Vector<N>)>
 where
-    T: Component,
-    T::Storage: Tracked,
-    D: Deref<Target = MaskedStorage<T>>,
+    N: RealField,
+    P: Deref<Target = MaskedStorage<Parent>>,
+    T: Deref<Target = MaskedStorage<Transform>>,
 {
-    let mut removed = BitSet::new();
-    for component_event in tracked_storage.channel().read(reader_id) {
-        match component_event {
-            ComponentEvent::Removed(id) => {
-                debug!("Got Removed event with id: {}", id);
-                removed.add(*id);
-            }
-            _ => {}
+    let transform = transforms.get(entity)?;
+    let mut isometry = transform_isometry::<N>(transform);
+    let mut scale = transform_scale::<N>(transform);
+
+    for ancestor in ancestors(entity, parents).skip(1) {
+        if let Some(transform) = transforms.get(ancestor) {
+            let parent_scale = transform_scale::<N>(transform);
+            isometry =
+                transform_isometry::<N>(transform) * scale_isometry(&isometry, &parent_scale);
+            scale = scale.component_mul(&parent_scale);
         }
     }
-    removed
+
+    Some((isometry, scale))
+}
+
+/// Converts the world space `Isometry` of an `Entity` into the local space of
+/// its `Parent` `Entity`, if any. This is the inverse of `world_transform(..)`
+/// for a single level of the `Parent` hierarchy.
+pub(crate) fn local_isometry<N, P, T>(
+    entity: Entity,
+    isometry: &Isometry<N>,
+    parents: &Storage<Parent, P>,
+    transforms: &Storage<Transform, T>,
+) -> Isometry<N>
+where
+    N: RealField,
+    P: Deref<Target = MaskedStorage<Parent>>,
+    T: Deref<Target = MaskedStorage<Transform>>,
+{
+    let parent_transform = parents
+        .get(entity)
+        .and_then(|parent| world_transform::<N, _, _>(parent.entity, parents, transforms));
+    match parent_transform {
+        Some((parent_isometry, parent_scale)) => scale_isometry(
+            &(parent_isometry.inverse() * isometry),
+            &parent_scale.map(|scale| N::one() / scale),
+        ),
+        None => *isometry,
+    }
+}
+
+/// Returns the depth of an `Entity` in the `Parent` hierarchy; root `Entity`s
+/// have a depth of zero.
+pub(crate) fn hierarchy_depth<P>(entity: Entity, parents: &Storage<Parent, P>) -> usize
+where
+    P: Deref<Target = MaskedStorage<Parent>>,
+{
+    ancestors(entity, parents).count() - 1
+}
+
+/// The `Ancestors` iterate over an `Entity` and all its ancestors in the
+/// `Parent` hierarchy, starting with the `Entity` itself. The iteration stops
+/// at the first `Entity` that was already visited, so cyclic `Parent`
+/// hierarchies terminate.
+pub(crate) struct Ancestors<'a, 'e, P>
+where
+    P: Deref<Target = MaskedStorage<Parent>>,
+{
+    parents: &'a Storage<'e, Parent, P>,
+    next: Option<Entity>,
+    visited: BitSet,
+}
+
+impl<'a, 'e, P> Iterator for Ancestors<'a, 'e, P>
+where
+    P: Deref<Target = MaskedStorage<Parent>>,
+{
+    type Item = Entity;
+
+    fn next(&mut self) -> Option<Entity> {
+        let current = self.next.take()?;
+        if self.visited.add(current.id()) {
+            warn!("Cyclic Parent hierarchy at {:?}", current);
+            return None;
+        }
+        self.next = self.parents.get(current).map(|parent| parent.entity);
+        Some(current)
+    }
+}
+
+/// Returns the `Ancestors` of the given `Entity`, starting with the `Entity`
+/// itself.
+pub(crate) fn ancestors<'a, 'e, P>(
+    entity: Entity,
+    parents: &'a Storage<'e, Parent, P>,
+) -> Ancestors<'a, 'e, P>
+where
+    P: Deref<Target = MaskedStorage<Parent>>,
+{
+    Ancestors {
+        parents,
+        next: Some(entity),
+        visited: BitSet::new(),
+    }
+}
+
+/// Walks up the `Parent` hierarchy of an `Entity`, starting with the `Entity`
+/// itself, and returns the first `Entity` owning a `RigidBody`.
+pub(crate) fn body_entity<P>(
+    entity: Entity,
+    parents: &Storage<Parent, P>,
+    physics_body_handles: &PhysicsBodyHandles,
+) -> Option<Entity>
+where
+    P: Deref<Target = MaskedStorage<Parent>>,
+{
+    ancestors(entity, parents).find(|ancestor| physics_body_handles.contains_key(&ancestor.id()))
+}
+
+/// Applies an `Isometry` from the `PhysicsWorld` to a `Transform`. This is the
+/// counterpart of `transform_isometry(..)`; in 2D the z translation of the
+/// `Transform` is left untouched.
+pub(crate) fn apply_isometry<N: RealField>(isometry: &Isometry<N>, transform: &mut Transform) {
+    #[cfg(feature = "dim2")]
+    {
+        let z = transform.translation().z;
+        transform.set_translation_xyz(
+            to_float(isometry.translation.vector.x),
+            to_float(isometry.translation.vector.y),
+            z,
+        );
+        transform.set_rotation_euler(
+            Float::from(0.0),
+            Float::from(0.0),
+            to_float(isometry.rotation.angle()),
+        );
+    }
+    #[cfg(feature = "dim3")]
+    {
+        transform.set_translation_xyz(
+            to_float(isometry.translation.vector.x),
+            to_float(isometry.translation.vector.y),
+            to_float(isometry.translation.vector.z),
+        );
+        let rotation = isometry.rotation.quaternion().coords;
+        *transform.rotation_mut() = math::UnitQuaternion::new_normalize(math::Quaternion::new(
+            to_float(rotation.w),
+            to_float(rotation.x),
+            to_float(rotation.y),
+            to_float(rotation.z),
+        ));
+    }
 }
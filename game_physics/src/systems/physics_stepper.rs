@@ -1,32 +1,134 @@
-use amethyst::ecs::{Resources, System, SystemData, WriteExpect};
+use std::{
+    marker::PhantomData,
+    time::{Duration, Instant},
+};
 
-use crate::PhysicsWorld;
+use amethyst::ecs::{ReadExpect, Resources, System, SystemData, Write, WriteExpect};
+use nphysics::object::{Body, BodyStatus};
+
+use crate::{
+    body::PhysicsBodyHandles,
+    collider::PhysicsColliderHandles,
+    contacts::{ContactImpulses, ImpulseRecorder},
+    math::RealField,
+    stats::PhysicsStats,
+    PhysicsWorld,
+};
 
 /// The `PhysicsStepperSystem` progresses the `PhysicsWorld` by calling:
 /// ```rust,ignore
 /// physics_world.step();
 /// ```
 ///
+/// The `PhysicsWorld` is set up with an `ImpulseRecorder` as its
+/// `ContactModel`, so the impulses applied at the contacts of the step are
+/// available to the `ContactsSystem` afterwards.
+///
+/// Afterwards the `PhysicsStats` are updated with the current body, collider
+/// and contact counts and the time spent in the step. If a log interval is
+/// set, a summary of the `PhysicsStats` is logged at most once per interval.
+///
 /// This `System` has to be executed after any `Motion`, `Gravity`,
 /// `PhysicsBody` or `PhysicsCollider` related `System`s.
-#[derive(Default)]
-pub struct PhysicsStepperSystem;
+pub struct PhysicsStepperSystem<N: RealField> {
+    log_interval: Option<Duration>,
+    last_log: Option<Instant>,
+    marker: PhantomData<N>,
+}
+
+impl<N: RealField> PhysicsStepperSystem<N> {
+    /// Creates a new `PhysicsStepperSystem` that logs a summary of the
+    /// `PhysicsStats` in the given interval.
+    pub fn new(log_interval: Option<Duration>) -> Self {
+        Self {
+            log_interval,
+            ..Self::default()
+        }
+    }
+}
 
-impl<'s> System<'s> for PhysicsStepperSystem {
-    type SystemData = WriteExpect<'s, PhysicsWorld>;
+impl<N: RealField> Default for PhysicsStepperSystem<N> {
+    fn default() -> Self {
+        Self {
+            log_interval: None,
+            last_log: None,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'s, N: RealField> System<'s> for PhysicsStepperSystem<N> {
+    type SystemData = (
+        ReadExpect<'s, PhysicsBodyHandles>,
+        ReadExpect<'s, PhysicsColliderHandles>,
+        ReadExpect<'s, ContactImpulses<N>>,
+        WriteExpect<'s, PhysicsWorld<N>>,
+        Write<'s, PhysicsStats>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            physics_body_handles,
+            physics_collider_handles,
+            contact_impulses,
+            mut physics_world,
+            mut physics_stats,
+        ) = data;
 
-    fn run(&mut self, mut physics_world: Self::SystemData) {
+        // the ImpulseRecorder only records the contacts solved in this step
+        contact_impulses.clear();
+        let start = Instant::now();
         physics_world.step();
+        physics_stats.step_time = start.elapsed();
+        physics_stats.steps += 1;
 
-        // print collisions for debug purposes
-        let collision_world = physics_world.collider_world();
-        collision_world.contact_events().iter().for_each(|event| {
-            info!("Got Contact: {:?}", event);
-        });
+        // count the RigidBodies by their status
+        physics_stats.dynamic_bodies = 0;
+        physics_stats.kinematic_bodies = 0;
+        physics_stats.static_bodies = 0;
+        physics_stats.disabled_bodies = 0;
+        physics_stats.sleeping_bodies = 0;
+        for handle in physics_body_handles.values() {
+            let rigid_body = match physics_world.rigid_body(*handle) {
+                Some(rigid_body) => rigid_body,
+                None => continue,
+            };
 
-        collision_world.proximity_events().iter().for_each(|event| {
-            info!("Got Proximity: {:?}", event);
-        });
+            match rigid_body.status() {
+                BodyStatus::Dynamic => {
+                    physics_stats.dynamic_bodies += 1;
+                    if !rigid_body.is_active() {
+                        physics_stats.sleeping_bodies += 1;
+                    }
+                }
+                BodyStatus::Kinematic => physics_stats.kinematic_bodies += 1,
+                BodyStatus::Static => physics_stats.static_bodies += 1,
+                BodyStatus::Disabled => physics_stats.disabled_bodies += 1,
+            }
+        }
+
+        // count the Colliders and the pairs of Colliders in contact
+        let colliders = physics_collider_handles
+            .values()
+            .filter_map(|handle| physics_world.collider(*handle))
+            .collect::<Vec<_>>();
+        physics_stats.colliders = colliders.len();
+        physics_stats.sensors = colliders
+            .iter()
+            .filter(|collider| collider.is_sensor())
+            .count();
+        physics_stats.contact_pairs = physics_world.collider_world().contact_pairs(true).count();
+
+        // log a summary of the PhysicsStats in place of every single event
+        if let Some(log_interval) = self.log_interval {
+            let now = Instant::now();
+            if self.last_log.map_or(true, |last_log| {
+                now.duration_since(last_log) >= log_interval
+            }) {
+                info!("PhysicsStats: {}", *physics_stats);
+                self.last_log = Some(now);
+            }
+        }
     }
 
     fn setup(&mut self, res: &mut Resources) {
@@ -34,6 +136,17 @@ impl<'s> System<'s> for PhysicsStepperSystem {
         Self::SystemData::setup(res);
 
         // initialise required resources
-        res.entry::<PhysicsWorld>().or_insert(PhysicsWorld::new());
+        res.entry::<PhysicsWorld<N>>()
+            .or_insert_with(PhysicsWorld::<N>::new);
+        res.entry::<PhysicsBodyHandles>()
+            .or_insert(PhysicsBodyHandles::new());
+        res.entry::<PhysicsColliderHandles>()
+            .or_insert(PhysicsColliderHandles::new());
+
+        // record the contact impulses applied by the solver
+        let contact_impulses = ContactImpulses::<N>::default();
+        res.fetch_mut::<PhysicsWorld<N>>()
+            .set_contact_model(ImpulseRecorder::new(contact_impulses.clone()));
+        res.insert(contact_impulses);
     }
 }
@@ -0,0 +1,90 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+use amethyst::{
+    ecs::{Resources, System, SystemData, Write},
+    error::Error,
+};
+
+use crate::{materials::PhysicsMaterials, math::RealField};
+
+/// The function used to load `PhysicsMaterials` from a file.
+pub(crate) type MaterialsLoader<N> = fn(&Path) -> Result<PhysicsMaterials<N>, Error>;
+
+/// The `ReloadMaterialsSystem` loads the `PhysicsMaterials` `Resource` from a
+/// RON file on setup and reloads it whenever the modification time of the file
+/// changes. The file is checked at most once per second. Hot-reloading can be
+/// disabled, e.g. in deterministic mode, where the wall-clock must not affect
+/// the simulation.
+pub struct ReloadMaterialsSystem<N: RealField> {
+    path: PathBuf,
+    loader: MaterialsLoader<N>,
+    hot_reload: bool,
+    modified: Option<SystemTime>,
+    last_check: Instant,
+}
+
+impl<N: RealField> ReloadMaterialsSystem<N> {
+    /// Creates a new `ReloadMaterialsSystem` for the given file, which is only
+    /// loaded once on setup unless `hot_reload` is enabled.
+    pub(crate) fn new(path: PathBuf, loader: MaterialsLoader<N>, hot_reload: bool) -> Self {
+        Self {
+            path,
+            loader,
+            hot_reload,
+            modified: None,
+            last_check: Instant::now(),
+        }
+    }
+
+    /// Returns the current modification time of the materials file.
+    fn modified(&self) -> Option<SystemTime> {
+        fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    /// Loads the materials file and merges it into the given
+    /// `PhysicsMaterials`.
+    fn reload(&mut self, physics_materials: &mut PhysicsMaterials<N>) {
+        self.modified = self.modified();
+
+        match (self.loader)(&self.path) {
+            Ok(materials) => {
+                info!("Loaded physics materials from {:?}", self.path);
+                physics_materials.reload(materials);
+            }
+            Err(err) => error!(
+                "Failed to load physics materials from {:?}: {}",
+                self.path, err
+            ),
+        }
+    }
+}
+
+impl<'s, N: RealField> System<'s> for ReloadMaterialsSystem<N> {
+    type SystemData = Write<'s, PhysicsMaterials<N>>;
+
+    fn run(&mut self, mut physics_materials: Self::SystemData) {
+        if !self.hot_reload || self.last_check.elapsed() < Duration::from_secs(1) {
+            return;
+        }
+        self.last_check = Instant::now();
+
+        if self.modified() != self.modified {
+            self.reload(&mut physics_materials);
+        }
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        info!("ReloadMaterialsSystem.setup");
+        Self::SystemData::setup(res);
+
+        // initially load the PhysicsMaterials
+        let mut physics_materials: Write<PhysicsMaterials<N>> = SystemData::fetch(&res);
+        self.reload(&mut physics_materials);
+    }
+}
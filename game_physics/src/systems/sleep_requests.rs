@@ -0,0 +1,101 @@
+use amethyst::{
+    ecs::{Read, ReadExpect, Resources, System, SystemData, WriteExpect},
+    shrev::ReaderId,
+};
+use ncollide::{bounding_volume::AABB, world::CollisionGroups};
+use nphysics::object::{Body, BodyHandle};
+
+use crate::{
+    body::PhysicsBodyHandles,
+    math::RealField,
+    sleep::{SleepRequest, SleepRequestChannel},
+    PhysicsWorld,
+};
+
+/// The `SleepRequestsSystem` handles all `SleepRequest`s published via the
+/// `SleepRequestChannel` by waking up or putting to sleep the affected
+/// `RigidBody`s.
+///
+/// This `System` has to be executed before the `PhysicsStepperSystem`.
+pub struct SleepRequestsSystem<N: RealField> {
+    sleep_requests_reader_id: Option<ReaderId<SleepRequest<N>>>,
+}
+
+impl<N: RealField> Default for SleepRequestsSystem<N> {
+    fn default() -> Self {
+        Self {
+            sleep_requests_reader_id: None,
+        }
+    }
+}
+
+impl<'s, N: RealField> System<'s> for SleepRequestsSystem<N> {
+    type SystemData = (
+        ReadExpect<'s, PhysicsBodyHandles>,
+        Read<'s, SleepRequestChannel<N>>,
+        WriteExpect<'s, PhysicsWorld<N>>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (physics_body_handles, sleep_requests, mut physics_world) = data;
+
+        for sleep_request in sleep_requests.read(self.sleep_requests_reader_id.as_mut().unwrap()) {
+            // collect the BodyHandles affected by the request and whether they should
+            // be woken up or put to sleep
+            let (handles, wake): (Vec<BodyHandle>, bool) = match sleep_request {
+                SleepRequest::Wake { entity } => (
+                    physics_body_handles
+                        .get(&entity.id())
+                        .cloned()
+                        .into_iter()
+                        .collect(),
+                    true,
+                ),
+                SleepRequest::Sleep { entity } => (
+                    physics_body_handles
+                        .get(&entity.id())
+                        .cloned()
+                        .into_iter()
+                        .collect(),
+                    false,
+                ),
+                SleepRequest::WakeArea { mins, maxs } => (
+                    physics_world
+                        .collider_world()
+                        .interferences_with_aabb(&AABB::new(*mins, *maxs), &CollisionGroups::new())
+                        .map(|collider| collider.body())
+                        .filter(|handle| !handle.is_ground())
+                        .collect(),
+                    true,
+                ),
+            };
+
+            for handle in handles {
+                if let Some(rigid_body) = physics_world.rigid_body_mut(handle) {
+                    if wake {
+                        rigid_body.activate();
+                    } else {
+                        rigid_body.deactivate();
+                    }
+                }
+            }
+
+            debug!("Handled sleep request: {:?}", sleep_request);
+        }
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        info!("SleepRequestsSystem.setup");
+        Self::SystemData::setup(res);
+
+        // initialise required resources
+        res.entry::<PhysicsWorld<N>>()
+            .or_insert_with(PhysicsWorld::<N>::new);
+        res.entry::<PhysicsBodyHandles>()
+            .or_insert(PhysicsBodyHandles::new());
+
+        // register reader id for the SleepRequestChannel
+        self.sleep_requests_reader_id =
+            Some(res.fetch_mut::<SleepRequestChannel<N>>().register_reader());
+    }
+}
@@ -0,0 +1,106 @@
+use std::marker::PhantomData;
+
+use amethyst::ecs::{
+    Entities,
+    Join,
+    ReadExpect,
+    ReadStorage,
+    Resources,
+    System,
+    SystemData,
+    Write,
+    WriteStorage,
+};
+use nphysics::object::{Body, BodyStatus};
+
+use crate::{
+    body::{PhysicsBody, PhysicsBodyHandles},
+    math::RealField,
+    sleep::{SleepEvent, SleepEventChannel, Sleeping},
+    PhysicsWorld,
+};
+
+/// The `SleepStatesSystem` synchronises the sleep state of all dynamic
+/// `RigidBody`s with the `Sleeping` marker `Component` of their `Entity`s after
+/// each step and publishes a `SleepEvent` for every transition.
+///
+/// This `System` has to be executed after the `PhysicsStepperSystem`.
+pub struct SleepStatesSystem<N: RealField> {
+    marker: PhantomData<N>,
+}
+
+impl<N: RealField> Default for SleepStatesSystem<N> {
+    fn default() -> Self {
+        Self {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'s, N: RealField> System<'s> for SleepStatesSystem<N> {
+    type SystemData = (
+        Entities<'s>,
+        ReadExpect<'s, PhysicsBodyHandles>,
+        ReadExpect<'s, PhysicsWorld<N>>,
+        ReadStorage<'s, PhysicsBody<N>>,
+        Write<'s, SleepEventChannel>,
+        WriteStorage<'s, Sleeping>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            physics_body_handles,
+            physics_world,
+            physics_bodies,
+            mut sleep_events,
+            mut sleeping,
+        ) = data;
+
+        for (entity, physics_body) in (&entities, &physics_bodies).join() {
+            // only dynamic RigidBodies can fall asleep; all others are never active
+            let asleep = physics_body.body_status == BodyStatus::Dynamic
+                && physics_body_handles
+                    .get(&entity.id())
+                    .and_then(|handle| physics_world.rigid_body(*handle))
+                    .map_or(false, |rigid_body| !rigid_body.is_active());
+
+            if asleep == sleeping.contains(entity) {
+                continue;
+            }
+
+            if asleep {
+                debug!("Entity {:?} fell asleep", entity);
+                if let Err(err) = sleeping.insert(entity, Sleeping) {
+                    error!("Failed to mark {:?} as sleeping: {}", entity, err);
+                    continue;
+                }
+                sleep_events.single_write(SleepEvent::Asleep { entity });
+            } else {
+                debug!("Entity {:?} woke up", entity);
+                sleeping.remove(entity);
+                sleep_events.single_write(SleepEvent::Awake { entity });
+            }
+        }
+
+        // clean up Sleeping markers of Entities that lost their PhysicsBody
+        let orphaned = (&entities, &sleeping, !&physics_bodies)
+            .join()
+            .map(|(entity, ..)| entity)
+            .collect::<Vec<_>>();
+        for entity in orphaned {
+            sleeping.remove(entity);
+        }
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        info!("SleepStatesSystem.setup");
+        Self::SystemData::setup(res);
+
+        // initialise required resources
+        res.entry::<PhysicsWorld<N>>()
+            .or_insert_with(PhysicsWorld::<N>::new);
+        res.entry::<PhysicsBodyHandles>()
+            .or_insert(PhysicsBodyHandles::new());
+    }
+}
@@ -0,0 +1,309 @@
+use std::{marker::PhantomData, time::Instant};
+
+use amethyst::{
+    core::{transform::Transform, Parent},
+    ecs::{
+        prelude::ParallelIterator,
+        storage::ComponentEvent,
+        BitSet,
+        Entities,
+        Join,
+        ParJoin,
+        ReadStorage,
+        ReaderId,
+        Resources,
+        System,
+        SystemData,
+        Write,
+        WriteExpect,
+        WriteStorage,
+    },
+};
+use nphysics::{
+    math::Velocity,
+    object::{Body, RigidBodyDesc},
+};
+
+use crate::{
+    body::{PhysicsBody, PhysicsBodyHandles},
+    error::{PhysicsError, PhysicsErrors},
+    math::{self, RealField},
+    snapshot::RestoredBodyStates,
+    stats::{PhysicsStats, SyncStats},
+    systems::{component_changes, modified_components, world_transform},
+    PhysicsWorld,
+};
+
+/// The `SyncBodiesSystem` synchronises `PhysicsBody` `Component`s with their
+/// `RigidBody` counterparts in the `PhysicsWorld`. All `ComponentEvent`s of a
+/// frame are read at once and applied in batches:
+/// - the `RigidBody`s of removed `PhysicsBody`s are removed with a single call
+/// - `RigidBody`s are created for inserted `PhysicsBody`s; this requires the
+///   `Entity` to also contain a `Transform` `Component`, otherwise the
+///   `PhysicsBody` is kept pending until the `Transform` is added; a
+///   `PhysicsError` is reported once when it becomes pending
+/// - modified `PhysicsBody`s update everything but the position of their
+///   `RigidBody`, which is only updated if the `Transform` was modified as well
+///
+/// Velocities and sleep states restored from a `PhysicsSnapshot` are applied
+/// once the `RigidBody` of their `Entity` was created or updated.
+///
+/// The world space positions are resolved through the complete `Parent`
+/// hierarchy of the `Entity` in parallel, before the `PhysicsWorld` is
+/// changed. In deterministic mode the resolved batches are additionally
+/// sorted by `Entity`, so the order `RigidBody`s are created and updated in
+/// never depends on the parallel join.
+pub struct SyncBodiesSystem<N: RealField> {
+    physics_bodies_reader_id: Option<ReaderId<ComponentEvent>>,
+    transforms_reader_id: Option<ReaderId<ComponentEvent>>,
+    pending: BitSet,
+    deterministic: bool,
+    marker: PhantomData<N>,
+}
+
+impl<N: RealField> SyncBodiesSystem<N> {
+    /// Creates a new `SyncBodiesSystem`, optionally in deterministic mode.
+    pub fn new(deterministic: bool) -> Self {
+        Self {
+            deterministic,
+            ..Self::default()
+        }
+    }
+}
+
+impl<N: RealField> Default for SyncBodiesSystem<N> {
+    fn default() -> Self {
+        Self {
+            physics_bodies_reader_id: None,
+            transforms_reader_id: None,
+            pending: BitSet::new(),
+            deterministic: false,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'s, N: RealField> System<'s> for SyncBodiesSystem<N> {
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, Parent>,
+        ReadStorage<'s, Transform>,
+        WriteExpect<'s, PhysicsBodyHandles>,
+        WriteExpect<'s, PhysicsWorld<N>>,
+        WriteStorage<'s, PhysicsBody<N>>,
+        Write<'s, PhysicsErrors>,
+        Write<'s, PhysicsStats>,
+        Write<'s, RestoredBodyStates<N>>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            parents,
+            transforms,
+            mut physics_body_handles,
+            mut physics_world,
+            mut physics_bodies,
+            mut physics_errors,
+            mut physics_stats,
+            mut restored_body_states,
+        ) = data;
+
+        // read all ComponentEvents of this frame at once
+        let changes = component_changes(
+            &physics_bodies,
+            self.physics_bodies_reader_id.as_mut().unwrap(),
+        );
+        let modified_transforms =
+            modified_components(&transforms, self.transforms_reader_id.as_mut().unwrap());
+
+        // remove the RigidBodies of all removed PhysicsBody components with a single
+        // call; re-inserted PhysicsBody components replace their previous RigidBody
+        let start = Instant::now();
+        let mut removed_handles = Vec::new();
+        for id in (&changes.removed | &changes.inserted).join() {
+            if let Some(handle) = physics_body_handles.remove(&id) {
+                if !changes.removed.contains(id) {
+                    warn!("Removing orphaned body handle: {:?}", handle);
+                }
+                removed_handles.push(handle);
+            }
+        }
+        if !removed_handles.is_empty() {
+            physics_world.remove_bodies(&removed_handles);
+        }
+        physics_stats.remove_rigid_bodies = SyncStats {
+            time: start.elapsed(),
+            events: (&changes.removed).join().count(),
+        };
+
+        // resolve the world space positions of all inserted and pending PhysicsBody
+        // components in parallel; removed PhysicsBody components drop out of the
+        // join
+        let start = Instant::now();
+        let mut candidates = changes.inserted.clone();
+        candidates |= &self.pending;
+        let previously_pending = std::mem::replace(&mut self.pending, BitSet::new());
+        let mut inserted = (&entities, &physics_bodies, &candidates)
+            .par_join()
+            .map(|(entity, ..)| {
+                let position = world_transform::<N, _, _>(entity, &parents, &transforms)
+                    .map(|(position, _)| position);
+                (entity, position)
+            })
+            .collect::<Vec<_>>();
+        if self.deterministic {
+            inserted.sort_by_key(|(entity, _)| entity.id());
+        }
+
+        // create the RigidBodies in the PhysicsWorld and store their handles;
+        // PhysicsBody components without a Transform cannot be positioned in the
+        // PhysicsWorld yet and are retried on the next frame
+        let delta_time = physics_world.timestep();
+        for (entity, position) in inserted {
+            let position = match position {
+                Some(position) => position,
+                None => {
+                    // only report the PhysicsError once the PhysicsBody becomes pending
+                    self.pending.add(entity.id());
+                    if !previously_pending.contains(entity.id()) {
+                        physics_errors.single_write(PhysicsError::MissingTransform { entity });
+                    }
+                    continue;
+                }
+            };
+            let physics_body = match physics_bodies.get_mut(entity) {
+                Some(physics_body) => physics_body,
+                None => continue,
+            };
+
+            let rigid_body = RigidBodyDesc::new()
+                .position(position)
+                .gravity_enabled(physics_body.gravity_enabled)
+                .status(physics_body.body_status)
+                .velocity(Velocity::<N>::new(
+                    physics_body.velocity / delta_time,
+                    math::zero(),
+                ))
+                .angular_inertia(physics_body.angular_inertia)
+                .mass(physics_body.mass)
+                .local_center_of_mass(physics_body.local_center_of_mass)
+                .user_data(entity)
+                .build(&mut physics_world);
+
+            // apply the simulated state restored from a PhysicsSnapshot
+            if let Some(state) = restored_body_states.remove(&entity) {
+                state.apply(rigid_body);
+            }
+            let handle = rigid_body.handle();
+
+            physics_body.handle = Some(handle);
+            physics_body_handles.insert(entity.id(), handle);
+
+            trace!(
+                "Inserted rigid body to world with values: {:?}",
+                physics_body
+            );
+        }
+        physics_stats.add_rigid_bodies = SyncStats {
+            time: start.elapsed(),
+            events: (&changes.inserted).join().count(),
+        };
+
+        // resolve the world space positions of all modified PhysicsBody components in
+        // parallel; only PhysicsBody components whose Transform was modified as well
+        // are repositioned
+        let start = Instant::now();
+        let mut updated = (&entities, &physics_bodies, &transforms, &changes.modified)
+            .par_join()
+            .map(|(entity, physics_body, _, id)| {
+                let position = if modified_transforms.contains(id) {
+                    world_transform::<N, _, _>(entity, &parents, &transforms)
+                        .map(|(position, _)| position)
+                } else {
+                    None
+                };
+                (entity, physics_body.handle, position)
+            })
+            .collect::<Vec<_>>();
+        if self.deterministic {
+            updated.sort_by_key(|(entity, ..)| entity.id());
+        }
+
+        // update the RigidBodies in the PhysicsWorld; pending PhysicsBody components
+        // are created with their latest values once their Transform is added
+        for (entity, handle, position) in updated {
+            if self.pending.contains(entity.id()) {
+                continue;
+            }
+
+            let rigid_body = match handle.and_then(|handle| physics_world.rigid_body_mut(handle)) {
+                Some(rigid_body) => rigid_body,
+                None => {
+                    physics_errors.single_write(PhysicsError::MissingRigidBody { entity });
+                    continue;
+                }
+            };
+            let physics_body = match physics_bodies.get(entity) {
+                Some(physics_body) => physics_body,
+                None => continue,
+            };
+
+            // the PhysicsBody was modified, update everything but the position
+            rigid_body.enable_gravity(physics_body.gravity_enabled);
+            rigid_body.set_status(physics_body.body_status);
+            rigid_body.set_velocity(Velocity::<N>::new(
+                physics_body.velocity / delta_time,
+                math::zero(),
+            ));
+
+            // derived mass properties are maintained by the MassPropertiesSystem
+            if !physics_body.mass_from_colliders {
+                rigid_body.set_angular_inertia(physics_body.angular_inertia);
+                rigid_body.set_mass(physics_body.mass);
+                rigid_body.set_local_center_of_mass(physics_body.local_center_of_mass);
+            }
+
+            // the Transform was modified, update the position directly
+            if let Some(position) = position {
+                rigid_body.set_position(position);
+            }
+
+            // apply the simulated state restored from a PhysicsSnapshot
+            if let Some(state) = restored_body_states.remove(&entity) {
+                state.apply(rigid_body);
+            }
+
+            trace!(
+                "Updated rigid body in world with values: {:?}",
+                physics_body
+            );
+        }
+        // drop restored states of PhysicsBody components removed in the meantime
+        restored_body_states.retain(|entity, _| physics_bodies.contains(*entity));
+
+        physics_stats.update_rigid_bodies = SyncStats {
+            time: start.elapsed(),
+            events: (&changes.modified).join().count(),
+        };
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        info!("SyncBodiesSystem.setup");
+        Self::SystemData::setup(res);
+
+        // initialise required resources
+        res.entry::<PhysicsWorld<N>>()
+            .or_insert_with(PhysicsWorld::<N>::new);
+        res.entry::<PhysicsBodyHandles>()
+            .or_insert(PhysicsBodyHandles::new());
+
+        // register reader id for the PhysicsBody storage
+        let mut physics_body_storage: WriteStorage<PhysicsBody<N>> = SystemData::fetch(&res);
+        self.physics_bodies_reader_id = Some(physics_body_storage.register_reader());
+
+        // register reader id for the Transform storage
+        let mut transform_storage: WriteStorage<Transform> = SystemData::fetch(&res);
+        self.transforms_reader_id = Some(transform_storage.register_reader());
+    }
+}
@@ -0,0 +1,544 @@
+use std::{collections::HashMap, marker::PhantomData, ops::Deref, time::Instant};
+
+use amethyst::{
+    core::{
+        transform::{ParentHierarchy, Transform},
+        Parent,
+    },
+    ecs::{
+        prelude::ParallelIterator,
+        storage::{ComponentEvent, MaskedStorage},
+        BitSet,
+        Entities,
+        Entity,
+        Index,
+        Join,
+        ParJoin,
+        Read,
+        ReadExpect,
+        ReadStorage,
+        ReaderId,
+        Resources,
+        Storage,
+        System,
+        SystemData,
+        Write,
+        WriteExpect,
+        WriteStorage,
+    },
+};
+use nphysics::object::{BodyHandle, BodyPartHandle, ColliderDesc, ColliderHandle};
+
+use crate::{
+    body::{PhysicsBody, PhysicsBodyHandles},
+    collider::{PhysicsCollider, PhysicsColliderHandles},
+    error::{PhysicsError, PhysicsErrors},
+    layers::CollisionLayers,
+    materials::PhysicsMaterials,
+    math::RealField,
+    stats::{PhysicsStats, SyncStats},
+    systems::{ancestors, component_changes, transform_scale, world_transform},
+    Isometry,
+    PhysicsWorld,
+    Vector,
+};
+
+/// The `ColliderPlacement` is the world space transform of a
+/// `PhysicsCollider`s `Entity` and of the `Entity` owning the `RigidBody` the
+/// `Collider` is attached to, resolved ahead of creating the `Collider`.
+struct ColliderPlacement<N: RealField> {
+    isometry: Isometry<N>,
+    scale: Vector<N>,
+    owner: Option<(BodyHandle, Isometry<N>)>,
+}
+
+/// Walks up the `Parent` hierarchy of an `Entity`, starting with the `Entity`
+/// itself, and returns the first `Entity` with a `PhysicsBody`, whether or not
+/// its `RigidBody` has been created yet.
+fn owner_entity<N, P, B>(
+    entity: Entity,
+    parents: &Storage<Parent, P>,
+    physics_bodies: &Storage<PhysicsBody<N>, B>,
+) -> Option<Entity>
+where
+    N: RealField,
+    P: Deref<Target = MaskedStorage<Parent>>,
+    B: Deref<Target = MaskedStorage<PhysicsBody<N>>>,
+{
+    ancestors(entity, parents).find(|ancestor| physics_bodies.contains(*ancestor))
+}
+
+/// Resolves the `ColliderPlacement` of the given `Entity`. The owner is the
+/// closest `Entity` in the `Parent` hierarchy with a `PhysicsBody`, starting
+/// with the `Entity` itself. Fails if the `Entity` has no `Transform` or if
+/// the `RigidBody` of the owner has not been created yet.
+fn collider_placement<N, P, T, B>(
+    entity: Entity,
+    parents: &Storage<Parent, P>,
+    transforms: &Storage<Transform, T>,
+    physics_bodies: &Storage<PhysicsBody<N>, B>,
+    physics_body_handles: &PhysicsBodyHandles,
+) -> Result<ColliderPlacement<N>, PhysicsError>
+where
+    N: RealField,
+    P: Deref<Target = MaskedStorage<Parent>>,
+    T: Deref<Target = MaskedStorage<Transform>>,
+    B: Deref<Target = MaskedStorage<PhysicsBody<N>>>,
+{
+    let (isometry, scale) = world_transform::<N, _, _>(entity, parents, transforms)
+        .ok_or(PhysicsError::MissingTransform { entity })?;
+    let owner = match owner_entity(entity, parents, physics_bodies) {
+        Some(owner) => {
+            let body_handle = *physics_body_handles
+                .get(&owner.id())
+                .ok_or(PhysicsError::MissingRigidBody { entity: owner })?;
+            let (owner_isometry, _) = world_transform::<N, _, _>(owner, parents, transforms)
+                .ok_or(PhysicsError::MissingTransform { entity: owner })?;
+            Some((body_handle, owner_isometry))
+        }
+        None => None,
+    };
+
+    Ok(ColliderPlacement {
+        isometry,
+        scale,
+        owner,
+    })
+}
+
+/// Creates the `Collider` for the given `PhysicsCollider` in the
+/// `PhysicsWorld`, placed and scaled by the given `ColliderPlacement`. The
+/// `Collider` is attached to the `RigidBody` of the owning `Entity`, if any;
+/// ultimately it defaults to the ground. Returns `None` if the `Collider`
+/// could not be attached, e.g. because the `RigidBody` of the owning `Entity`
+/// no longer exists in the `PhysicsWorld`.
+fn build_collider<N: RealField>(
+    entity: Entity,
+    placement: &ColliderPlacement<N>,
+    physics_collider: &mut PhysicsCollider<N>,
+    collision_layers: &CollisionLayers,
+    physics_world: &mut PhysicsWorld<N>,
+) -> Option<ColliderHandle> {
+    // remember the scale the Collider is built with
+    physics_collider.scale = placement.scale;
+    let position = placement.isometry * physics_collider.scaled_offset_from_parent();
+
+    // the Collider is positioned relative to the owning Entity
+    let owner = match placement.owner {
+        Some((body_handle, owner_isometry)) => {
+            let part_handle = physics_world.rigid_body(body_handle)?.part_handle();
+            Some((body_handle, part_handle, owner_isometry))
+        }
+        None => None,
+    };
+    let (parent_part_handle, position) = match owner {
+        Some((_, part_handle, owner_isometry)) => {
+            (part_handle, owner_isometry.inverse() * position)
+        }
+        // ultimately default to BodyPartHandle::ground() with a world space position
+        None => (BodyPartHandle::ground(), position),
+    };
+    physics_collider.position_wrt_body = position;
+    physics_collider.body_handle = owner.map(|(body_handle, ..)| body_handle);
+
+    // create the actual Collider in the PhysicsWorld and fetch its handle
+    let handle = ColliderDesc::new(physics_collider.shape_handle())
+        .position(position)
+        .density(physics_collider.density)
+        .material(physics_collider.material.clone())
+        .margin(physics_collider.margin)
+        .collision_groups(collision_layers.resolve(physics_collider))
+        .linear_prediction(physics_collider.linear_prediction)
+        .angular_prediction(physics_collider.angular_prediction)
+        .sensor(physics_collider.sensor)
+        .user_data(entity)
+        .build_with_parent(parent_part_handle, physics_world)
+        .map(|collider| collider.handle());
+
+    physics_collider.handle = handle;
+    handle
+}
+
+/// The `SyncCollidersSystem` synchronises `PhysicsCollider` `Component`s with
+/// their `Collider` counterparts in the `PhysicsWorld`. All `ComponentEvent`s
+/// of a frame are read at once and applied in batches:
+/// - the `Collider`s of removed `PhysicsCollider`s are removed with a single
+///   call
+/// - `Collider`s are created for inserted `PhysicsCollider`s; this requires the
+///   `Entity` to also contain a `Transform` `Component` and the `RigidBody` of
+///   the owning `Entity` to exist, otherwise the `PhysicsCollider` is kept
+///   pending until both are present; a `PhysicsError` is reported once when
+///   it becomes pending
+/// - modified `PhysicsCollider`s update the collision groups of their
+///   `Collider`
+/// - `Collider`s are rebuilt whenever the world space scale of their `Entity`
+///   or the `RigidBody` they belong to changes, e.g. if a `PhysicsBody` or
+///   `Parent` is added after the `Collider` was created; only `Entity`s with a
+///   `Parent` or `PhysicsBody` event or a rescaled, inserted or removed
+///   `Transform` in this frame and their descendants in the `ParentHierarchy`
+///   are checked; the `Transform`s written by the `SyncPositionsSystem` only
+///   move their `Entity`s and are skipped by comparing the local scale with
+///   the last one seen
+///
+/// The `Collider`s are placed and scaled based on the complete `Parent`
+/// hierarchy of the `Entity`, which is resolved in parallel before the
+/// `PhysicsWorld` is changed. They are attached to the `RigidBody` of the
+/// closest `Entity` in the `Parent` hierarchy, starting with the `Entity`
+/// itself; ultimately they default to the ground. In deterministic mode the
+/// resolved batches are additionally sorted by `Entity`, so the order
+/// `Collider`s are created in never depends on the parallel join.
+pub struct SyncCollidersSystem<N: RealField> {
+    physics_colliders_reader_id: Option<ReaderId<ComponentEvent>>,
+    transforms_reader_id: Option<ReaderId<ComponentEvent>>,
+    parents_reader_id: Option<ReaderId<ComponentEvent>>,
+    physics_bodies_reader_id: Option<ReaderId<ComponentEvent>>,
+    collision_layers: Option<CollisionLayers>,
+    pending: BitSet,
+    scales: HashMap<Index, Vector<N>>,
+    deterministic: bool,
+    marker: PhantomData<N>,
+}
+
+impl<N: RealField> SyncCollidersSystem<N> {
+    /// Creates a new `SyncCollidersSystem` that inserts the given
+    /// `CollisionLayers` `Resource` on setup, optionally in deterministic
+    /// mode.
+    pub fn new(collision_layers: Option<CollisionLayers>, deterministic: bool) -> Self {
+        Self {
+            collision_layers,
+            deterministic,
+            ..Self::default()
+        }
+    }
+}
+
+impl<N: RealField> Default for SyncCollidersSystem<N> {
+    fn default() -> Self {
+        Self {
+            physics_colliders_reader_id: None,
+            transforms_reader_id: None,
+            parents_reader_id: None,
+            physics_bodies_reader_id: None,
+            collision_layers: None,
+            pending: BitSet::new(),
+            scales: HashMap::new(),
+            deterministic: false,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'s, N: RealField> System<'s> for SyncCollidersSystem<N> {
+    type SystemData = (
+        Entities<'s>,
+        Read<'s, CollisionLayers>,
+        ReadExpect<'s, PhysicsBodyHandles>,
+        Option<ReadExpect<'s, ParentHierarchy>>,
+        ReadStorage<'s, Parent>,
+        ReadStorage<'s, PhysicsBody<N>>,
+        ReadStorage<'s, Transform>,
+        Write<'s, PhysicsMaterials<N>>,
+        WriteExpect<'s, PhysicsColliderHandles>,
+        WriteExpect<'s, PhysicsWorld<N>>,
+        WriteStorage<'s, PhysicsCollider<N>>,
+        Write<'s, PhysicsErrors>,
+        Write<'s, PhysicsStats>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            collision_layers,
+            physics_body_handles,
+            parent_hierarchy,
+            parent_entities,
+            physics_bodies,
+            transforms,
+            mut physics_materials,
+            mut physics_collider_handles,
+            mut physics_world,
+            mut physics_colliders,
+            mut physics_errors,
+            mut physics_stats,
+        ) = data;
+
+        // read all ComponentEvents of this frame at once
+        let changes = component_changes(
+            &physics_colliders,
+            self.physics_colliders_reader_id.as_mut().unwrap(),
+        );
+
+        // collect the Entities whose world space scale or owning RigidBody may have
+        // changed in this frame
+        let mut hierarchy_changes = BitSet::new();
+        for changes in &[
+            component_changes(&parent_entities, self.parents_reader_id.as_mut().unwrap()),
+            component_changes(
+                &physics_bodies,
+                self.physics_bodies_reader_id.as_mut().unwrap(),
+            ),
+        ] {
+            hierarchy_changes |= &changes.inserted;
+            hierarchy_changes |= &changes.modified;
+            hierarchy_changes |= &changes.removed;
+        }
+
+        // modified Transform components only count if their local scale changed, as
+        // the positions of all moving RigidBodies are written back every frame
+        let transform_changes =
+            component_changes(&transforms, self.transforms_reader_id.as_mut().unwrap());
+        for id in (&transform_changes.removed).join() {
+            self.scales.remove(&id);
+        }
+        hierarchy_changes |= &transform_changes.removed;
+        for (entity, transform, _) in (
+            &entities,
+            &transforms,
+            &transform_changes.inserted | &transform_changes.modified,
+        )
+            .join()
+        {
+            let scale = transform_scale::<N>(transform);
+            if self.scales.insert(entity.id(), scale) != Some(scale) {
+                hierarchy_changes.add(entity.id());
+            }
+        }
+
+        // remove the Colliders of all removed PhysicsCollider components with a
+        // single call; re-inserted PhysicsCollider components replace their previous
+        // Collider
+        let start = Instant::now();
+        let mut removed_handles = Vec::new();
+        for id in (&changes.removed | &changes.inserted).join() {
+            if let Some(handle) = physics_collider_handles.remove(&id) {
+                if !changes.removed.contains(id) {
+                    warn!("Removing orphaned collider handle: {:?}", handle);
+                }
+                // the Collider is already gone if its RigidBody was removed
+                if physics_world.collider(handle).is_some() {
+                    removed_handles.push(handle);
+                }
+            }
+        }
+        if !removed_handles.is_empty() {
+            physics_world.remove_colliders(&removed_handles);
+        }
+        physics_stats.remove_colliders = SyncStats {
+            time: start.elapsed(),
+            events: (&changes.removed).join().count(),
+        };
+
+        // resolve the placements of all inserted and pending PhysicsCollider
+        // components in parallel; removed PhysicsCollider components drop out of the
+        // join
+        let start = Instant::now();
+        let mut candidates = changes.inserted.clone();
+        candidates |= &self.pending;
+        let previously_pending = std::mem::replace(&mut self.pending, BitSet::new());
+        let mut inserted = (&entities, &physics_colliders, &candidates)
+            .par_join()
+            .map(|(entity, ..)| {
+                let placement = collider_placement::<N, _, _, _>(
+                    entity,
+                    &parent_entities,
+                    &transforms,
+                    &physics_bodies,
+                    &physics_body_handles,
+                );
+                (entity, placement)
+            })
+            .collect::<Vec<_>>();
+        if self.deterministic {
+            inserted.sort_by_key(|(entity, _)| entity.id());
+        }
+
+        // create the Colliders in the PhysicsWorld and store their handles;
+        // PhysicsCollider components without a Transform or RigidBody to attach to
+        // cannot be placed in the PhysicsWorld yet and are retried on the next frame
+        for (entity, placement) in inserted {
+            let placement = match placement {
+                Ok(placement) => placement,
+                Err(physics_error) => {
+                    // only report the PhysicsError once the PhysicsCollider becomes pending
+                    self.pending.add(entity.id());
+                    if !previously_pending.contains(entity.id()) {
+                        physics_errors.single_write(physics_error);
+                    }
+                    continue;
+                }
+            };
+            let physics_collider = match physics_colliders.get_mut(entity) {
+                Some(physics_collider) => physics_collider,
+                None => continue,
+            };
+
+            // resolve the named material to its shared MaterialHandle
+            if let Some(ref material_name) = physics_collider.material_name {
+                match physics_materials.handle(material_name) {
+                    Some(material) => physics_collider.material = material,
+                    None => warn!("Unknown physics material: {}", material_name),
+                }
+            }
+
+            let handle = match build_collider(
+                entity,
+                &placement,
+                physics_collider,
+                &collision_layers,
+                &mut physics_world,
+            ) {
+                Some(handle) => handle,
+                None => {
+                    physics_errors.single_write(PhysicsError::ColliderCreation { entity });
+                    continue;
+                }
+            };
+            physics_collider_handles.insert(entity.id(), handle);
+
+            trace!(
+                "Inserted collider to world with values: {:?}",
+                physics_collider
+            );
+        }
+        physics_stats.add_colliders = SyncStats {
+            time: start.elapsed(),
+            events: (&changes.inserted).join().count(),
+        };
+
+        // update the collision groups of all modified PhysicsCollider components;
+        // pending PhysicsCollider components are created with their latest values
+        let start = Instant::now();
+        let collider_world = physics_world.collider_world_mut();
+        for (entity, physics_collider, _) in
+            (&entities, &physics_colliders, &changes.modified).join()
+        {
+            if self.pending.contains(entity.id()) {
+                continue;
+            }
+            let handle = match physics_collider.handle {
+                Some(handle) => handle,
+                None => {
+                    physics_errors.single_write(PhysicsError::MissingCollider { entity });
+                    continue;
+                }
+            };
+            collider_world.set_collision_groups(handle, collision_layers.resolve(physics_collider));
+
+            trace!(
+                "Updated collider in world with values: {:?}",
+                physics_collider
+            );
+        }
+
+        // the changes propagate to all descendants in the Parent hierarchy; without a
+        // ParentHierarchy all PhysicsCollider components have to be checked
+        let affected = match parent_hierarchy {
+            Some(ref parent_hierarchy) => {
+                let mut affected = hierarchy_changes.clone();
+                for id in (&hierarchy_changes).join() {
+                    affected |= &parent_hierarchy.all_children(entities.entity(id));
+                }
+                affected
+            }
+            None => physics_colliders.mask().clone(),
+        };
+
+        // collect the affected PhysicsCollider components whose world space scale or
+        // owning RigidBody changed in parallel and resolve their new placements
+        let mut rebuilt = (&entities, &physics_colliders, &affected)
+            .par_join()
+            .filter(|(entity, physics_collider, _)| {
+                if physics_collider.handle.is_none() {
+                    return false;
+                }
+                let rescaled = world_transform::<N, _, _>(*entity, &parent_entities, &transforms)
+                    .map_or(false, |(_, scale)| scale != physics_collider.scale);
+                let body_handle = owner_entity(*entity, &parent_entities, &physics_bodies)
+                    .and_then(|owner| physics_body_handles.get(&owner.id()).cloned());
+                rescaled || body_handle != physics_collider.body_handle
+            })
+            .filter_map(|(entity, ..)| {
+                collider_placement::<N, _, _, _>(
+                    entity,
+                    &parent_entities,
+                    &transforms,
+                    &physics_bodies,
+                    &physics_body_handles,
+                )
+                .ok()
+                .map(|placement| (entity, placement))
+            })
+            .collect::<Vec<_>>();
+        if self.deterministic {
+            rebuilt.sort_by_key(|(entity, _)| entity.id());
+        }
+
+        // rebuild the Colliders of rescaled or reattached PhysicsCollider components
+        for (entity, placement) in rebuilt {
+            let physics_collider = match physics_colliders.get_mut(entity) {
+                Some(physics_collider) => physics_collider,
+                None => continue,
+            };
+
+            // the Collider is already gone if its previous RigidBody was removed
+            if let Some(handle) = physics_collider_handles.remove(&entity.id()) {
+                if physics_world.collider(handle).is_some() {
+                    physics_world.remove_colliders(&[handle]);
+                }
+            }
+
+            let handle = match build_collider(
+                entity,
+                &placement,
+                physics_collider,
+                &collision_layers,
+                &mut physics_world,
+            ) {
+                Some(handle) => handle,
+                None => {
+                    physics_errors.single_write(PhysicsError::ColliderCreation { entity });
+                    continue;
+                }
+            };
+            physics_collider_handles.insert(entity.id(), handle);
+
+            debug!(
+                "Rebuilt collider in world with values: {:?}",
+                physics_collider
+            );
+        }
+        physics_stats.update_colliders = SyncStats {
+            time: start.elapsed(),
+            events: (&changes.modified).join().count(),
+        };
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        info!("SyncCollidersSystem.setup");
+        Self::SystemData::setup(res);
+
+        // initialise required resources
+        res.entry::<PhysicsWorld<N>>()
+            .or_insert_with(PhysicsWorld::<N>::new);
+        res.entry::<PhysicsBodyHandles>()
+            .or_insert(PhysicsBodyHandles::new());
+        res.entry::<PhysicsColliderHandles>()
+            .or_insert(PhysicsColliderHandles::new());
+        if let Some(collision_layers) = self.collision_layers.take() {
+            res.insert(collision_layers);
+        }
+
+        // register reader id for the PhysicsCollider storage
+        let mut physics_collider_storage: WriteStorage<PhysicsCollider<N>> =
+            SystemData::fetch(&res);
+        self.physics_colliders_reader_id = Some(physics_collider_storage.register_reader());
+
+        // register reader ids for the storages affecting the placement of Colliders
+        let mut transform_storage: WriteStorage<Transform> = SystemData::fetch(&res);
+        self.transforms_reader_id = Some(transform_storage.register_reader());
+        let mut parent_storage: WriteStorage<Parent> = SystemData::fetch(&res);
+        self.parents_reader_id = Some(parent_storage.register_reader());
+        let mut physics_body_storage: WriteStorage<PhysicsBody<N>> = SystemData::fetch(&res);
+        self.physics_bodies_reader_id = Some(physics_body_storage.register_reader());
+    }
+}
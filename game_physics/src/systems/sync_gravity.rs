@@ -1,13 +1,24 @@
-use crate::{Gravity, PhysicsWorld};
+use std::marker::PhantomData;
+
+use crate::{math::RealField, Gravity, PhysicsWorld};
 use amethyst::ecs::{ReadExpect, Resources, System, SystemData, WriteExpect};
 
 /// The `SyncGravitySystem` handles the synchronisation of `Gravity`
 /// changes to the `PhysicsWorld`.
-#[derive(Default)]
-pub struct SyncGravitySystem;
+pub struct SyncGravitySystem<N: RealField> {
+    marker: PhantomData<N>,
+}
+
+impl<N: RealField> Default for SyncGravitySystem<N> {
+    fn default() -> Self {
+        Self {
+            marker: PhantomData,
+        }
+    }
+}
 
-impl<'s> System<'s> for SyncGravitySystem {
-    type SystemData = (ReadExpect<'s, Gravity>, WriteExpect<'s, PhysicsWorld>);
+impl<'s, N: RealField> System<'s> for SyncGravitySystem<N> {
+    type SystemData = (ReadExpect<'s, Gravity<N>>, WriteExpect<'s, PhysicsWorld<N>>);
 
     fn run(&mut self, (gravity, mut physics_world): Self::SystemData) {
         physics_world.set_gravity(*gravity);
@@ -18,7 +29,7 @@ impl<'s> System<'s> for SyncGravitySystem {
         Self::SystemData::setup(res);
 
         // initialise required resources
-        res.entry::<Gravity>()
-            .or_insert_with(|| Gravity::new(0.0, 0.0, 0.0));
+        res.entry::<Gravity<N>>()
+            .or_insert_with(Gravity::<N>::zeros);
     }
 }
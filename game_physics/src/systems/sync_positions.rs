@@ -1,37 +1,86 @@
+use std::marker::PhantomData;
+
 use amethyst::{
-    core::{Float, Transform},
-    ecs::{Join, ReadExpect, ReadStorage, Resources, System, SystemData, WriteStorage},
+    core::{Parent, Transform},
+    ecs::{
+        Entities,
+        Join,
+        ReadExpect,
+        ReadStorage,
+        Resources,
+        System,
+        SystemData,
+        Write,
+        WriteStorage,
+    },
 };
-use nalgebra::Isometry3;
 
-use crate::{body::PhysicsBody, PhysicsWorld};
+use crate::{
+    body::PhysicsBody,
+    error::{PhysicsError, PhysicsErrors},
+    math::RealField,
+    systems::{apply_isometry, hierarchy_depth, local_isometry},
+    PhysicsWorld,
+};
 
 /// The `SyncPositionsSystem` synchronised the updated position of the
 /// `RigidBody`s in the `PhysicsWorld` with their Amethyst counterparts. This
-/// affects the actual `Transform` `Component` related to the `Entity`.
-#[derive(Default)]
-pub struct SyncPositionsSystem;
+/// affects the actual `Transform` `Component` related to the `Entity`. The
+/// world space positions of `RigidBody`s are converted into the local space of
+/// the `Parent` `Entity`, if any. `PhysicsBody`s without a `RigidBody` are
+/// skipped and reported as `PhysicsError`s.
+pub struct SyncPositionsSystem<N: RealField> {
+    marker: PhantomData<N>,
+}
 
-impl<'s> System<'s> for SyncPositionsSystem {
+impl<N: RealField> Default for SyncPositionsSystem<N> {
+    fn default() -> Self {
+        Self {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'s, N: RealField> System<'s> for SyncPositionsSystem<N> {
     type SystemData = (
-        ReadExpect<'s, PhysicsWorld>,
-        ReadStorage<'s, PhysicsBody>,
+        Entities<'s>,
+        ReadExpect<'s, PhysicsWorld<N>>,
+        ReadStorage<'s, Parent>,
+        ReadStorage<'s, PhysicsBody<N>>,
         WriteStorage<'s, Transform>,
+        Write<'s, PhysicsErrors>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (physics_world, physics_bodies, mut transforms) = data;
-
-        // iterate over all PhysicBody components that also come with a Transform
-        for (physics_body, transform) in (&physics_bodies, &mut transforms).join() {
-            if let Some(rigid_body) = physics_world.rigid_body(physics_body.handle.unwrap()) {
-                let isometry: &Isometry3<f32> = rigid_body.position();
-
-                transform.set_translation_xyz(
-                    Float::from(isometry.translation.vector.x),
-                    Float::from(isometry.translation.vector.y),
-                    Float::from(isometry.translation.vector.z),
-                );
+        let (entities, physics_world, parents, physics_bodies, mut transforms, mut physics_errors) =
+            data;
+
+        // collect all PhysicBody components that also come with a Transform and sort
+        // them by their depth in the Parent hierarchy; parents have to be synchronised
+        // before their children are placed relative to them
+        let mut bodies = (&entities, &physics_bodies, &transforms)
+            .join()
+            .map(|(entity, physics_body, _)| {
+                let depth = hierarchy_depth(entity, &parents);
+                (depth, entity, physics_body.handle)
+            })
+            .collect::<Vec<_>>();
+        bodies.sort_by_key(|(depth, ..)| *depth);
+
+        for (_, entity, handle) in bodies {
+            let rigid_body = match handle.and_then(|handle| physics_world.rigid_body(handle)) {
+                Some(rigid_body) => rigid_body,
+                None => {
+                    physics_errors.single_write(PhysicsError::MissingRigidBody { entity });
+                    continue;
+                }
+            };
+
+            // convert the world space position into the local space of the parent Entity
+            let position = local_isometry(entity, rigid_body.position(), &parents, &transforms);
+
+            if let Some(transform) = transforms.get_mut(entity) {
+                apply_isometry(&position, transform);
             }
         }
     }
@@ -0,0 +1,155 @@
+use std::{
+    collections::{HashMap, HashSet},
+    marker::PhantomData,
+};
+
+use amethyst::{
+    core::{Float, Parent, Transform},
+    ecs::{world::Index, Entities, Entity, Join, Resources, System, SystemData, WriteStorage},
+};
+
+use crate::{
+    collider::PhysicsCollider,
+    contact_filter::OneWayPlatform,
+    math::RealField,
+    systems::to_float,
+    tile_grid::{Tile, TileGrid, TileRect},
+};
+
+/// The `TileGridsSystem` creates a child `Entity` with a `PhysicsCollider` for
+/// every merged rectangle of a `TileGrid`. Whenever the `Tile`s of a
+/// `TileGrid` change, the rectangles are merged anew and only the child
+/// `Entity`s of rectangles that no longer exist are deleted, while new ones
+/// are created; unchanged rectangles keep their `PhysicsCollider`s unless the
+/// size of the tiles changed, which replaces all rectangles. The child
+/// `Entity`s are deleted together with the `TileGrid`.
+///
+/// This `System` has to be executed before the `SyncCollidersSystem`.
+pub struct TileGridsSystem<N: RealField> {
+    rects: HashMap<Index, (Entity, HashMap<TileRect, Entity>)>,
+    marker: PhantomData<N>,
+}
+
+impl<N: RealField> Default for TileGridsSystem<N> {
+    fn default() -> Self {
+        Self {
+            rects: HashMap::new(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'s, N: RealField> System<'s> for TileGridsSystem<N> {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, TileGrid<N>>,
+        WriteStorage<'s, Parent>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, PhysicsCollider<N>>,
+        WriteStorage<'s, OneWayPlatform<N>>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            mut tile_grids,
+            mut parents,
+            mut transforms,
+            mut physics_colliders,
+            mut one_way_platforms,
+        ) = data;
+
+        // delete the child Entities of removed TileGrids; Entities are deleted in
+        // the order of their Index, as the order decides which Index is reused next
+        let mut removed = self
+            .rects
+            .iter()
+            .filter(|(_, (entity, _))| !entities.is_alive(*entity) || !tile_grids.contains(*entity))
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        removed.sort();
+        for id in removed {
+            let (entity, rects) = self.rects.remove(&id).unwrap();
+            debug!("Removing TileGrid colliders of {:?}", entity);
+            let mut children = rects.values().cloned().collect::<Vec<_>>();
+            children.sort_by_key(|child| child.id());
+            for child in children {
+                if let Err(err) = entities.delete(child) {
+                    error!("Failed to delete TileGrid collider {:?}: {}", child, err);
+                }
+            }
+        }
+
+        for (entity, tile_grid) in (&entities, &mut tile_grids).join() {
+            if !tile_grid.dirty {
+                continue;
+            }
+            let resized = tile_grid.resized;
+            tile_grid.dirty = false;
+            tile_grid.resized = false;
+
+            let (_, existing) = self
+                .rects
+                .entry(entity.id())
+                .or_insert_with(|| (entity, HashMap::new()));
+            let merged_rects = tile_grid.merge_tiles();
+            let merged = merged_rects.iter().cloned().collect::<HashSet<_>>();
+
+            // delete the child Entities of rectangles that no longer exist or were
+            // resized
+            let mut stale = existing
+                .iter()
+                .filter(|(rect, _)| resized || !merged.contains(rect))
+                .map(|(rect, child)| (*rect, *child))
+                .collect::<Vec<_>>();
+            stale.sort_by_key(|(_, child)| child.id());
+            for (rect, child) in stale {
+                existing.remove(&rect);
+                if let Err(err) = entities.delete(child) {
+                    error!("Failed to delete TileGrid collider {:?}: {}", child, err);
+                }
+            }
+
+            // create the child Entities of new rectangles in the order they were merged
+            let mut created = 0;
+            for rect in merged_rects {
+                if existing.contains_key(&rect) {
+                    continue;
+                }
+
+                let (shape, (x, y)) = tile_grid.rect_shape(&rect);
+                let mut physics_collider = tile_grid.physics_collider.clone();
+                physics_collider.shape = shape;
+                physics_collider.handle = None;
+
+                let mut transform = Transform::default();
+                transform.set_translation_xyz(to_float(x), to_float(y), Float::from(0.0));
+
+                let child = entities.create();
+                parents.insert(child, Parent { entity }).unwrap();
+                transforms.insert(child, transform).unwrap();
+                physics_colliders.insert(child, physics_collider).unwrap();
+                if rect.tile == Tile::OneWay {
+                    one_way_platforms
+                        .insert(child, OneWayPlatform::default())
+                        .unwrap();
+                }
+
+                existing.insert(rect, child);
+                created += 1;
+            }
+
+            debug!(
+                "Rebuilt TileGrid colliders of {:?}: {} rectangles, {} created",
+                entity,
+                existing.len(),
+                created
+            );
+        }
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        info!("TileGridsSystem.setup");
+        Self::SystemData::setup(res);
+    }
+}
@@ -0,0 +1,210 @@
+use std::{
+    collections::{HashMap, HashSet},
+    marker::PhantomData,
+};
+
+use amethyst::ecs::{
+    storage::ComponentEvent,
+    Entities,
+    Entity,
+    Join,
+    ReadExpect,
+    ReaderId,
+    Resources,
+    System,
+    SystemData,
+    Write,
+    WriteStorage,
+};
+use ncollide::query::Proximity;
+
+use crate::{
+    collider::PhysicsColliderHandles,
+    math::RealField,
+    systems::{collider_entity, component_changes},
+    trigger::{TriggerEvent, TriggerEventChannel, TriggerVolume},
+    PhysicsWorld,
+};
+
+/// The `TriggerVolumesSystem` maintains the set of overlapping `Entity`s of
+/// every `TriggerVolume` based on the proximity events of the last
+/// `PhysicsWorld` step and publishes `TriggerEvent`s for `Entity`s entering
+/// or leaving a `TriggerVolume`. Newly inserted `TriggerVolume`s are seeded
+/// with the proximities already in progress, as no proximity event is
+/// published for them.
+///
+/// This `System` has to be executed after the `PhysicsStepperSystem`.
+pub struct TriggerVolumesSystem<N: RealField> {
+    trigger_volumes_reader_id: Option<ReaderId<ComponentEvent>>,
+    overlaps: HashMap<Entity, HashSet<Entity>>,
+    marker: PhantomData<N>,
+}
+
+impl<N: RealField> Default for TriggerVolumesSystem<N> {
+    fn default() -> Self {
+        Self {
+            trigger_volumes_reader_id: None,
+            overlaps: HashMap::new(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'s, N: RealField> System<'s> for TriggerVolumesSystem<N> {
+    type SystemData = (
+        Entities<'s>,
+        ReadExpect<'s, PhysicsColliderHandles>,
+        ReadExpect<'s, PhysicsWorld<N>>,
+        Write<'s, TriggerEventChannel>,
+        WriteStorage<'s, TriggerVolume>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            physics_collider_handles,
+            physics_world,
+            mut trigger_events,
+            mut trigger_volumes,
+        ) = data;
+
+        let mut changed_triggers = HashSet::new();
+
+        // iterate over all proximity events of the last step; every event is checked
+        // for both Colliders as either of them could belong to a TriggerVolume
+        for event in physics_world.collider_world().proximity_events() {
+            let entered = event.new_status == Proximity::Intersecting;
+            let exited = event.prev_status == Proximity::Intersecting && !entered;
+            if !entered && !exited {
+                continue;
+            }
+
+            let (entity1, entity2) = match (
+                collider_entity(&physics_world, event.collider1),
+                collider_entity(&physics_world, event.collider2),
+            ) {
+                (Some(entity1), Some(entity2)) => (entity1, entity2),
+                _ => continue,
+            };
+
+            for (trigger, entity, handle) in &[
+                (entity1, entity2, event.collider2),
+                (entity2, entity1, event.collider1),
+            ] {
+                let trigger_volume = match trigger_volumes.get(*trigger) {
+                    Some(trigger_volume) => trigger_volume,
+                    None => continue,
+                };
+
+                if entered {
+                    let accepted = physics_world.collider(*handle).map_or(false, |collider| {
+                        trigger_volume.accepts(collider.collision_groups())
+                    });
+
+                    if accepted && self.overlaps.entry(*trigger).or_default().insert(*entity) {
+                        debug!("Entity {:?} entered trigger {:?}", entity, trigger);
+                        trigger_events.single_write(TriggerEvent::Enter {
+                            trigger: *trigger,
+                            entity: *entity,
+                        });
+                        changed_triggers.insert(*trigger);
+                    }
+                } else if self
+                    .overlaps
+                    .get_mut(trigger)
+                    .map_or(false, |overlaps| overlaps.remove(entity))
+                {
+                    debug!("Entity {:?} exited trigger {:?}", entity, trigger);
+                    trigger_events.single_write(TriggerEvent::Exit {
+                        trigger: *trigger,
+                        entity: *entity,
+                    });
+                    changed_triggers.insert(*trigger);
+                }
+            }
+        }
+
+        // seed inserted TriggerVolume components with the Entities already overlapping
+        // their Collider
+        let changes = component_changes(
+            &trigger_volumes,
+            self.trigger_volumes_reader_id.as_mut().unwrap(),
+        );
+        for (trigger, trigger_volume, _) in (&entities, &trigger_volumes, &changes.inserted).join()
+        {
+            changed_triggers.insert(trigger);
+            let trigger_handle = match physics_collider_handles.get(&trigger.id()) {
+                Some(handle) => *handle,
+                None => continue,
+            };
+
+            for (handle1, handle2, _) in physics_world.collider_world().proximity_pairs(true) {
+                let handle = if handle1 == trigger_handle {
+                    handle2
+                } else if handle2 == trigger_handle {
+                    handle1
+                } else {
+                    continue;
+                };
+                let entity = match collider_entity(&physics_world, handle) {
+                    Some(entity) => entity,
+                    None => continue,
+                };
+                let accepted = physics_world.collider(handle).map_or(false, |collider| {
+                    trigger_volume.accepts(collider.collision_groups())
+                });
+
+                if accepted && self.overlaps.entry(trigger).or_default().insert(entity) {
+                    debug!("Entity {:?} entered trigger {:?}", entity, trigger);
+                    trigger_events.single_write(TriggerEvent::Enter { trigger, entity });
+                }
+            }
+        }
+
+        // remove overlaps of deleted Entities or Entities without Collider; no
+        // proximity event is guaranteed in these cases
+        let has_collider = |entity: &Entity| {
+            entities.is_alive(*entity) && physics_collider_handles.contains_key(&entity.id())
+        };
+        for (trigger, overlaps) in self.overlaps.iter_mut() {
+            let trigger_valid = has_collider(trigger) && trigger_volumes.contains(*trigger);
+
+            overlaps.retain(|entity| {
+                if trigger_valid && has_collider(entity) {
+                    return true;
+                }
+
+                debug!("Entity {:?} removed from trigger {:?}", entity, trigger);
+                trigger_events.single_write(TriggerEvent::Exit {
+                    trigger: *trigger,
+                    entity: *entity,
+                });
+                changed_triggers.insert(*trigger);
+                false
+            });
+        }
+        self.overlaps.retain(|_, overlaps| !overlaps.is_empty());
+
+        // synchronise the changed overlaps with their TriggerVolume components
+        for trigger in changed_triggers {
+            if let Some(trigger_volume) = trigger_volumes.get_mut(trigger) {
+                trigger_volume.entities = self.overlaps.get(&trigger).cloned().unwrap_or_default();
+            }
+        }
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        info!("TriggerVolumesSystem.setup");
+        Self::SystemData::setup(res);
+
+        // initialise required resources
+        res.entry::<PhysicsWorld<N>>()
+            .or_insert_with(PhysicsWorld::<N>::new);
+        res.entry::<PhysicsColliderHandles>()
+            .or_insert(PhysicsColliderHandles::new());
+
+        // register reader id for the TriggerVolume storage
+        let mut trigger_volume_storage: WriteStorage<TriggerVolume> = SystemData::fetch(&res);
+        self.trigger_volumes_reader_id = Some(trigger_volume_storage.register_reader());
+    }
+}
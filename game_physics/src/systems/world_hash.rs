@@ -0,0 +1,79 @@
+use std::{hash::Hasher, marker::PhantomData};
+
+use amethyst::ecs::{ReadExpect, Resources, System, SystemData, Write};
+use nphysics::object::{Body, BodyStatus};
+
+use crate::{
+    body::PhysicsBodyHandles,
+    math::RealField,
+    world_hash::{PhysicsWorldHash, WorldHasher},
+    PhysicsWorld,
+};
+
+/// The `PhysicsWorldHashSystem` updates the `PhysicsWorldHash` `Resource`
+/// after each step. The `RigidBody`s are hashed in the order of the `Index` of
+/// their `Entity`, which is independent of the iteration order of the
+/// `PhysicsBodyHandles`.
+pub struct PhysicsWorldHashSystem<N: RealField> {
+    marker: PhantomData<N>,
+}
+
+impl<N: RealField> Default for PhysicsWorldHashSystem<N> {
+    fn default() -> Self {
+        Self {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'s, N: RealField> System<'s> for PhysicsWorldHashSystem<N> {
+    type SystemData = (
+        ReadExpect<'s, PhysicsBodyHandles>,
+        ReadExpect<'s, PhysicsWorld<N>>,
+        Write<'s, PhysicsWorldHash>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (physics_body_handles, physics_world, mut physics_world_hash) = data;
+
+        let mut bodies = physics_body_handles.iter().collect::<Vec<_>>();
+        bodies.sort_by_key(|(id, _)| **id);
+
+        let mut hasher = WorldHasher::new();
+        for (id, handle) in bodies {
+            let rigid_body = match physics_world.rigid_body(*handle) {
+                Some(rigid_body) => rigid_body,
+                None => continue,
+            };
+
+            hasher.write_u32(*id);
+            hasher.write_u8(match rigid_body.status() {
+                BodyStatus::Dynamic => 0,
+                BodyStatus::Kinematic => 1,
+                BodyStatus::Static => 2,
+                BodyStatus::Disabled => 3,
+            });
+            hasher.write_u8(u8::from(rigid_body.is_active()));
+            for value in rigid_body.position().to_homogeneous().iter() {
+                hasher.write_scalar(*value);
+            }
+            for value in rigid_body.velocity().as_slice() {
+                hasher.write_scalar(*value);
+            }
+        }
+
+        physics_world_hash.step += 1;
+        physics_world_hash.hash = hasher.finish();
+    }
+
+    fn setup(&mut self, res: &mut Resources) {
+        info!("PhysicsWorldHashSystem.setup");
+        Self::SystemData::setup(res);
+
+        // initialise required resources
+        res.entry::<PhysicsWorld<N>>()
+            .or_insert_with(PhysicsWorld::<N>::new);
+        res.entry::<PhysicsBodyHandles>()
+            .or_insert(PhysicsBodyHandles::new());
+    }
+}
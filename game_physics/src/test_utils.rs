@@ -0,0 +1,333 @@
+//! Utilities for testing the `PhysicsBundle` without a window or renderer.
+//! This module is only available with the `test-utils` feature enabled.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use game_physics::{body::BodyStatus, test_utils::PhysicsHarness, PhysicsBodyBuilder, Vector};
+//!
+//! let mut harness = PhysicsHarness::<f32>::new().with_gravity(Vector::new(0.0, -10.0, 0.0));
+//! let entity = harness.spawn_body(
+//!     PhysicsBodyBuilder::from(BodyStatus::Dynamic).gravity_enabled(true),
+//!     Vector::zeros(),
+//! );
+//!
+//! harness.run(60);
+//! assert!(harness.translation(entity).unwrap().y < 0.0);
+//! ```
+
+use std::marker::PhantomData;
+
+use amethyst::{
+    core::{bundle::SystemBundle, transform::TransformBundle, Parent, Transform},
+    ecs::{
+        Builder,
+        Component,
+        Dispatcher,
+        DispatcherBuilder,
+        Entity,
+        EntityBuilder,
+        ReaderId,
+        World,
+    },
+    shrev::{Event, EventChannel},
+};
+use nphysics::object::{BodyHandle, ColliderHandle};
+
+use crate::{
+    body::{PhysicsBodyBuilder, PhysicsBodyHandles},
+    collider::{PhysicsColliderBuilder, PhysicsColliderHandles},
+    math::RealField,
+    systems::{to_float, transform_isometry, world_transform},
+    Gravity,
+    Isometry,
+    PhysicsBundle,
+    PhysicsWorld,
+    PhysicsWorldHash,
+    Vector,
+};
+
+/// The `PhysicsHarness` runs the `TransformBundle` and the `PhysicsBundle` on
+/// a plain specs `World`, so the synchronisation between Amethyst and the
+/// `PhysicsWorld` can be tested headless. Every call to `step()` runs the
+/// `Dispatcher` once and maintains the `World` afterwards, just like a single
+/// frame of a running game.
+pub struct PhysicsHarness<N: RealField = f32> {
+    pub world: World,
+    dispatcher: Dispatcher<'static, 'static>,
+    marker: PhantomData<N>,
+}
+
+impl<N: RealField> PhysicsHarness<N> {
+    /// Creates a new `PhysicsHarness` running the default `PhysicsBundle`.
+    pub fn new() -> Self {
+        Self::from_bundle(PhysicsBundle::default())
+    }
+
+    /// Creates a new `PhysicsHarness` running the given, customised
+    /// `PhysicsBundle`.
+    pub fn from_bundle(physics_bundle: PhysicsBundle<N>) -> Self {
+        let mut world = World::new();
+
+        let mut dispatcher_builder = DispatcherBuilder::new();
+        TransformBundle::new()
+            .build(&mut dispatcher_builder)
+            .expect("Failed to build TransformBundle");
+        physics_bundle
+            .build(&mut dispatcher_builder)
+            .expect("Failed to build PhysicsBundle");
+
+        let mut dispatcher = dispatcher_builder.build();
+        dispatcher.setup(&mut world.res);
+
+        Self {
+            world,
+            dispatcher,
+            marker: PhantomData,
+        }
+    }
+
+    /// Sets the `Gravity` applied with the next step.
+    pub fn with_gravity(mut self, gravity: Vector<N>) -> Self {
+        self.set_gravity(gravity);
+        self
+    }
+
+    /// Sets the `Gravity` applied with the next step.
+    pub fn set_gravity(&mut self, gravity: Vector<N>) {
+        self.world.add_resource::<Gravity<N>>(gravity);
+    }
+
+    /// Starts building a new `Entity`.
+    pub fn create_entity(&mut self) -> EntityBuilder {
+        self.world.create_entity()
+    }
+
+    /// Creates a new `Entity` with a `PhysicsBody` at the given translation.
+    pub fn spawn_body(
+        &mut self,
+        physics_body: PhysicsBodyBuilder<N>,
+        translation: Vector<N>,
+    ) -> Entity {
+        self.world
+            .create_entity()
+            .with(physics_body.build())
+            .with(transform_at(&translation))
+            .build()
+    }
+
+    /// Creates a new `Entity` with a `PhysicsCollider` at the given
+    /// translation.
+    pub fn spawn_collider(
+        &mut self,
+        physics_collider: PhysicsColliderBuilder<N>,
+        translation: Vector<N>,
+    ) -> Entity {
+        self.world
+            .create_entity()
+            .with(physics_collider.build())
+            .with(transform_at(&translation))
+            .build()
+    }
+
+    /// Creates a new `Entity` with a `PhysicsBody` and `PhysicsCollider` at
+    /// the given translation.
+    pub fn spawn_body_with_collider(
+        &mut self,
+        physics_body: PhysicsBodyBuilder<N>,
+        physics_collider: PhysicsColliderBuilder<N>,
+        translation: Vector<N>,
+    ) -> Entity {
+        self.world
+            .create_entity()
+            .with(physics_body.build())
+            .with(physics_collider.build())
+            .with(transform_at(&translation))
+            .build()
+    }
+
+    /// Creates a new `Entity` with a `PhysicsCollider` that is a child of the
+    /// given `Entity`, placed at the given translation relative to it.
+    pub fn spawn_child_collider(
+        &mut self,
+        parent: Entity,
+        physics_collider: PhysicsColliderBuilder<N>,
+        translation: Vector<N>,
+    ) -> Entity {
+        self.world
+            .create_entity()
+            .with(physics_collider.build())
+            .with(transform_at(&translation))
+            .with(Parent { entity: parent })
+            .build()
+    }
+
+    /// Inserts a `Component` into the given `Entity`, replacing any previous
+    /// `Component` of the same type.
+    pub fn insert<C: Component>(&mut self, entity: Entity, component: C) {
+        self.world
+            .write_storage::<C>()
+            .insert(entity, component)
+            .expect("Failed to insert Component into dead Entity");
+    }
+
+    /// Removes a `Component` from the given `Entity`.
+    pub fn remove<C: Component>(&mut self, entity: Entity) -> Option<C> {
+        self.world.write_storage::<C>().remove(entity)
+    }
+
+    /// Modifies a `Component` of the given `Entity` in place, flagging it as
+    /// modified.
+    pub fn modify<C: Component, F: FnOnce(&mut C)>(&mut self, entity: Entity, f: F) {
+        let mut storage = self.world.write_storage::<C>();
+        f(storage
+            .get_mut(entity)
+            .expect("Entity has no such Component"));
+    }
+
+    /// Deletes the given `Entity` along with all its `Component`s.
+    pub fn delete(&mut self, entity: Entity) {
+        self.world
+            .delete_entity(entity)
+            .expect("Failed to delete dead Entity");
+    }
+
+    /// Runs the `Dispatcher` once and maintains the `World`.
+    pub fn step(&mut self) {
+        self.dispatcher.dispatch(&self.world.res);
+        self.world.maintain();
+    }
+
+    /// Runs the given number of steps.
+    pub fn run(&mut self, steps: usize) {
+        for _ in 0..steps {
+            self.step();
+        }
+    }
+
+    /// Registers a new reader for the `EventChannel` of the given `Event`
+    /// type. Only `Event`s published after the registration can be read.
+    pub fn register_reader<E: Event>(&mut self) -> ReaderId<E> {
+        self.world
+            .res
+            .entry::<EventChannel<E>>()
+            .or_insert_with(EventChannel::new)
+            .register_reader()
+    }
+
+    /// Returns all `Event`s published since the last read of the given reader.
+    pub fn read_events<E: Event + Clone>(&self, reader_id: &mut ReaderId<E>) -> Vec<E> {
+        self.world
+            .read_resource::<EventChannel<E>>()
+            .read(reader_id)
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the local translation of the `Transform` of the given `Entity`.
+    pub fn translation(&self, entity: Entity) -> Option<Vector<N>> {
+        self.world
+            .read_storage::<Transform>()
+            .get(entity)
+            .map(|transform| transform_isometry::<N>(transform).translation.vector)
+    }
+
+    /// Returns the world space translation of the given `Entity`, resolved
+    /// through its `Parent` hierarchy.
+    pub fn world_translation(&self, entity: Entity) -> Option<Vector<N>> {
+        world_transform::<N, _, _>(
+            entity,
+            &self.world.read_storage::<Parent>(),
+            &self.world.read_storage::<Transform>(),
+        )
+        .map(|(isometry, _)| isometry.translation.vector)
+    }
+
+    /// Returns the `BodyHandle` of the `RigidBody` of the given `Entity`.
+    pub fn body_handle(&self, entity: Entity) -> Option<BodyHandle> {
+        self.world
+            .read_resource::<PhysicsBodyHandles>()
+            .get(&entity.id())
+            .cloned()
+    }
+
+    /// Returns the `ColliderHandle` of the `Collider` of the given `Entity`.
+    pub fn collider_handle(&self, entity: Entity) -> Option<ColliderHandle> {
+        self.world
+            .read_resource::<PhysicsColliderHandles>()
+            .get(&entity.id())
+            .cloned()
+    }
+
+    /// Returns the position of the `RigidBody` of the given `Entity` in the
+    /// `PhysicsWorld`.
+    pub fn body_position(&self, entity: Entity) -> Option<Isometry<N>> {
+        let handle = self.body_handle(entity)?;
+        self.world
+            .read_resource::<PhysicsWorld<N>>()
+            .rigid_body(handle)
+            .map(|rigid_body| *rigid_body.position())
+    }
+
+    /// Returns the `BodyHandle` of the body the `Collider` of the given
+    /// `Entity` is attached to; this is the ground for static `Collider`s.
+    pub fn collider_body(&self, entity: Entity) -> Option<BodyHandle> {
+        let handle = self.collider_handle(entity)?;
+        self.world
+            .read_resource::<PhysicsWorld<N>>()
+            .collider(handle)
+            .map(|collider| collider.body())
+    }
+
+    /// Checks whether the `Collider` of the given `Entity` exists in the
+    /// `PhysicsWorld`.
+    pub fn has_collider(&self, entity: Entity) -> bool {
+        self.collider_handle(entity).map_or(false, |handle| {
+            self.world
+                .read_resource::<PhysicsWorld<N>>()
+                .collider(handle)
+                .is_some()
+        })
+    }
+
+    /// Returns the `PhysicsWorldHash` of the last step. It is only maintained
+    /// if the `PhysicsBundle` runs in deterministic mode.
+    pub fn world_hash(&self) -> Option<PhysicsWorldHash> {
+        self.world
+            .res
+            .try_fetch::<PhysicsWorldHash>()
+            .map(|physics_world_hash| *physics_world_hash)
+    }
+
+    /// Asserts that the local translation of the given `Entity` is within
+    /// `epsilon` of the expected translation.
+    pub fn assert_translation(&self, entity: Entity, expected: Vector<N>, epsilon: N) {
+        let translation = self
+            .translation(entity)
+            .unwrap_or_else(|| panic!("{:?} has no Transform", entity));
+        assert!(
+            (translation - expected).norm() <= epsilon,
+            "{:?} is at {:?}, expected {:?}",
+            entity,
+            translation,
+            expected
+        );
+    }
+}
+
+impl<N: RealField> Default for PhysicsHarness<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Creates a `Transform` at the given translation. In 2D the z translation is
+/// left at zero.
+pub fn transform_at<N: RealField>(translation: &Vector<N>) -> Transform {
+    let mut transform = Transform::default();
+    transform.set_translation_x(to_float(translation.x));
+    transform.set_translation_y(to_float(translation.y));
+    #[cfg(feature = "dim3")]
+    transform.set_translation_z(to_float(translation.z));
+    transform
+}
@@ -0,0 +1,367 @@
+use amethyst::ecs::{Component, DenseVecStorage};
+
+use crate::{
+    collider::{PhysicsCollider, PhysicsColliderBuilder, Shape},
+    math::{self, RealField},
+};
+
+/// The `Tile` defines the solidity of a single cell of a `TileGrid`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Tile {
+    /// The tile does not collide.
+    Empty,
+    /// The tile collides from all sides.
+    Solid,
+    /// The tile only collides from above; see `OneWayPlatform`.
+    OneWay,
+}
+
+impl Default for Tile {
+    fn default() -> Self {
+        Tile::Empty
+    }
+}
+
+/// The `TileRect` is a rectangle of equal, merged tiles in grid coordinates.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub(crate) struct TileRect {
+    pub(crate) x: usize,
+    pub(crate) y: usize,
+    pub(crate) width: usize,
+    pub(crate) height: usize,
+    pub(crate) tile: Tile,
+}
+
+/// The `TileGrid` `Component` generates `PhysicsCollider`s for a 2D grid of
+/// `Tile`s. Instead of one `PhysicsCollider` per tile, equal tiles are
+/// greedily merged into as few rectangles as possible, which avoids
+/// characters snagging on the internal edges between tiles. `Tile::OneWay`
+/// tiles are only merged along rows, so stacked one-way tiles remain separate
+/// platforms.
+///
+/// Each rectangle is created as a child `Entity` of the `TileGrid` `Entity`
+/// carrying a `PhysicsCollider` built from the given `PhysicsColliderBuilder`
+/// (and a `OneWayPlatform` for one-way tiles). The grid is laid out in the
+/// local space of the `Entity`: tile `(0, 0)` is the bottom left one and its
+/// bottom left corner is the origin. Changing tiles via `set_tile` only
+/// replaces the rectangles that actually changed, while changing the size of
+/// the tiles via `set_tile_size` or `set_depth` replaces all of them.
+///
+/// For more information on how the `PhysicsCollider`s are generated, see the
+/// following `System`:
+/// - `systems::tile_grids::TileGridsSystem`
+///
+/// # Example
+///
+/// ```rust
+/// use game_physics::{PhysicsColliderBuilder, Shape, Tile, TileGrid};
+///
+/// let mut tile_grid = TileGrid::<f32>::new(
+///     16,
+///     9,
+///     32.0,
+///     32.0,
+///     PhysicsColliderBuilder::from(Shape::Rectangle(0.0, 0.0, 1.0)).layer("ground"),
+/// );
+/// tile_grid.set_tile(3, 2, Tile::OneWay);
+/// ```
+#[derive(Clone)]
+pub struct TileGrid<N: RealField = f32> {
+    width: usize,
+    height: usize,
+    tiles: Vec<Tile>,
+    tile_width: N,
+    tile_height: N,
+    depth: N,
+    pub(crate) physics_collider: PhysicsCollider<N>,
+    /// Whether the `Tile`s changed since the last rebuild.
+    pub(crate) dirty: bool,
+    /// Whether the size of the tiles changed since the last rebuild; the
+    /// `TileRect`s do not reflect it, so all of them have to be replaced.
+    pub(crate) resized: bool,
+}
+
+impl<N: RealField> Component for TileGrid<N> {
+    type Storage = DenseVecStorage<Self>;
+}
+
+impl<N: RealField> TileGrid<N> {
+    /// Creates a new, empty `TileGrid` with the given dimensions in tiles and
+    /// size of a single tile.
+    pub fn new(
+        width: usize,
+        height: usize,
+        tile_width: N,
+        tile_height: N,
+        physics_collider: PhysicsColliderBuilder<N>,
+    ) -> Self {
+        Self {
+            width,
+            height,
+            tiles: vec![Tile::Empty; width * height],
+            tile_width,
+            tile_height,
+            depth: N::one(),
+            physics_collider: physics_collider.build(),
+            dirty: true,
+            resized: false,
+        }
+    }
+
+    /// Sets all `Tile`s of the `TileGrid` row by row, starting with the
+    /// bottom row. Missing tiles are left empty, surplus ones are ignored.
+    pub fn with_tiles<I: IntoIterator<Item = Tile>>(mut self, tiles: I) -> Self {
+        let mut tiles = tiles.into_iter();
+        for tile in self.tiles.iter_mut() {
+            *tile = tiles.next().unwrap_or_default();
+        }
+        self.dirty = true;
+        self
+    }
+
+    /// Sets the depth of the generated `Shape`s.
+    pub fn with_depth(mut self, depth: N) -> Self {
+        self.set_depth(depth);
+        self
+    }
+
+    /// Returns the width of the `TileGrid` in tiles.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height of the `TileGrid` in tiles.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the width of a single tile.
+    pub fn tile_width(&self) -> N {
+        self.tile_width
+    }
+
+    /// Returns the height of a single tile.
+    pub fn tile_height(&self) -> N {
+        self.tile_height
+    }
+
+    /// Sets the size of a single tile and schedules a rebuild of all
+    /// `PhysicsCollider`s.
+    pub fn set_tile_size(&mut self, tile_width: N, tile_height: N) {
+        if self.tile_width != tile_width || self.tile_height != tile_height {
+            self.tile_width = tile_width;
+            self.tile_height = tile_height;
+            self.dirty = true;
+            self.resized = true;
+        }
+    }
+
+    /// Returns the depth of the generated `Shape`s along the z axis; ignored
+    /// in 2D.
+    pub fn depth(&self) -> N {
+        self.depth
+    }
+
+    /// Sets the depth of the generated `Shape`s and schedules a rebuild of all
+    /// `PhysicsCollider`s.
+    pub fn set_depth(&mut self, depth: N) {
+        if self.depth != depth {
+            self.depth = depth;
+            self.dirty = true;
+            self.resized = true;
+        }
+    }
+
+    /// Returns the `Tile` at the given position; positions outside of the
+    /// `TileGrid` are empty.
+    pub fn tile(&self, x: usize, y: usize) -> Tile {
+        if x < self.width && y < self.height {
+            self.tiles[y * self.width + x]
+        } else {
+            Tile::Empty
+        }
+    }
+
+    /// Sets the `Tile` at the given position and schedules a rebuild of the
+    /// affected `PhysicsCollider`s. Positions outside of the `TileGrid` are
+    /// ignored.
+    pub fn set_tile(&mut self, x: usize, y: usize, tile: Tile) {
+        if x < self.width && y < self.height && self.tiles[y * self.width + x] != tile {
+            self.tiles[y * self.width + x] = tile;
+            self.dirty = true;
+        }
+    }
+
+    /// Greedily merges all non-empty `Tile`s into rectangles. Starting at the
+    /// first uncovered tile, every rectangle is grown along its row first and
+    /// then upwards for as long as all tiles match.
+    pub(crate) fn merge_tiles(&self) -> Vec<TileRect> {
+        let mut covered = vec![false; self.tiles.len()];
+        let mut rects = Vec::new();
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let tile = self.tiles[y * self.width + x];
+                if tile == Tile::Empty || covered[y * self.width + x] {
+                    continue;
+                }
+
+                let matches = |x: usize, y: usize| {
+                    self.tiles[y * self.width + x] == tile && !covered[y * self.width + x]
+                };
+
+                let mut width = 1;
+                while x + width < self.width && matches(x + width, y) {
+                    width += 1;
+                }
+
+                let mut height = 1;
+                if tile != Tile::OneWay {
+                    while y + height < self.height && (x..x + width).all(|x| matches(x, y + height))
+                    {
+                        height += 1;
+                    }
+                }
+
+                for y in y..y + height {
+                    for x in x..x + width {
+                        covered[y * self.width + x] = true;
+                    }
+                }
+
+                rects.push(TileRect {
+                    x,
+                    y,
+                    width,
+                    height,
+                    tile,
+                });
+            }
+        }
+
+        rects
+    }
+
+    /// Returns the `Shape` and the local position of its centre for the given
+    /// `TileRect`.
+    pub(crate) fn rect_shape(&self, rect: &TileRect) -> (Shape<N>, (N, N)) {
+        let (width, height): (N, N) = (
+            self.tile_width * math::convert(rect.width as f64),
+            self.tile_height * math::convert(rect.height as f64),
+        );
+        let center: (N, N) = (
+            self.tile_width * math::convert(rect.x as f64) + width / math::convert(2.0),
+            self.tile_height * math::convert(rect.y as f64) + height / math::convert(2.0),
+        );
+
+        (Shape::Rectangle(width, height, self.depth), center)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tile_grid(width: usize, height: usize, tiles: &[Tile]) -> TileGrid<f32> {
+        TileGrid::new(
+            width,
+            height,
+            1.0,
+            1.0,
+            PhysicsColliderBuilder::from(Shape::Rectangle(1.0, 1.0, 1.0)),
+        )
+        .with_tiles(tiles.iter().cloned())
+    }
+
+    fn rect(x: usize, y: usize, width: usize, height: usize, tile: Tile) -> TileRect {
+        TileRect {
+            x,
+            y,
+            width,
+            height,
+            tile,
+        }
+    }
+
+    #[test]
+    fn merge_tiles_grows_rows_before_columns() {
+        use super::Tile::{Empty as E, Solid as S};
+        // rows are given bottom up
+        let tile_grid = tile_grid(3, 3, &[S, S, S, S, S, E, S, S, E]);
+
+        assert_eq!(
+            tile_grid.merge_tiles(),
+            vec![rect(0, 0, 3, 1, S), rect(0, 1, 2, 2, S)]
+        );
+    }
+
+    #[test]
+    fn merge_tiles_keeps_different_tiles_apart() {
+        use super::Tile::{OneWay as O, Solid as S};
+        let tile_grid = tile_grid(3, 1, &[S, O, S]);
+
+        assert_eq!(
+            tile_grid.merge_tiles(),
+            vec![
+                rect(0, 0, 1, 1, S),
+                rect(1, 0, 1, 1, O),
+                rect(2, 0, 1, 1, S)
+            ]
+        );
+    }
+
+    #[test]
+    fn merge_tiles_merges_one_way_tiles_along_rows_only() {
+        use super::Tile::OneWay as O;
+        let tile_grid = tile_grid(2, 2, &[O, O, O, O]);
+
+        assert_eq!(
+            tile_grid.merge_tiles(),
+            vec![rect(0, 0, 2, 1, O), rect(0, 1, 2, 1, O)]
+        );
+    }
+
+    #[test]
+    fn set_tile_only_changes_affected_rects() {
+        use super::Tile::{Empty as E, OneWay as O, Solid as S};
+        let mut tile_grid = tile_grid(3, 3, &[S, S, S, E, E, E, O, O, O]);
+        tile_grid.dirty = false;
+
+        tile_grid.set_tile(0, 2, E);
+
+        assert!(tile_grid.dirty);
+        assert!(!tile_grid.resized);
+        assert_eq!(
+            tile_grid.merge_tiles(),
+            vec![rect(0, 0, 3, 1, S), rect(1, 2, 2, 1, O)]
+        );
+    }
+
+    #[test]
+    fn set_tile_ignores_unchanged_and_outside_tiles() {
+        use super::Tile::Solid as S;
+        let mut tile_grid = tile_grid(2, 1, &[S, S]);
+        tile_grid.dirty = false;
+
+        tile_grid.set_tile(0, 0, S);
+        tile_grid.set_tile(2, 0, Tile::Empty);
+
+        assert!(!tile_grid.dirty);
+    }
+
+    #[test]
+    fn set_tile_size_resizes_all_rects() {
+        let mut tile_grid = tile_grid(2, 1, &[Tile::Solid, Tile::Solid]);
+        tile_grid.dirty = false;
+
+        tile_grid.set_tile_size(2.0, 3.0);
+        tile_grid.set_depth(4.0);
+
+        assert!(tile_grid.dirty);
+        assert!(tile_grid.resized);
+        assert_eq!(
+            tile_grid.rect_shape(&rect(0, 0, 2, 1, Tile::Solid)),
+            (Shape::Rectangle(4.0, 3.0, 4.0), (2.0, 1.5))
+        );
+    }
+}
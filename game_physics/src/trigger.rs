@@ -0,0 +1,81 @@
+use std::collections::HashSet;
+
+use amethyst::{
+    ecs::{Component, DenseVecStorage, Entity, FlaggedStorage},
+    shrev::EventChannel,
+};
+use ncollide::world::CollisionGroups;
+
+/// The `TriggerVolume` `Component` keeps track of all `Entity`s that currently
+/// overlap the sensor `PhysicsCollider` of the same `Entity`. The set of
+/// overlapping `Entity`s is maintained from the proximity events of the
+/// `PhysicsWorld` after each step; entering and leaving `Entity`s are
+/// additionally published as `TriggerEvent`s via the `TriggerEventChannel`.
+/// A `TriggerVolume` added to a sensor that already overlaps other `Entity`s
+/// starts with these `Entity`s.
+///
+/// The `Entity` of a `TriggerVolume` requires a `PhysicsCollider` that was
+/// created with `PhysicsColliderBuilder::sensor(true)`.
+///
+/// For more information on how the overlaps are tracked, see the following
+/// `System`:
+/// - `systems::trigger_volumes::TriggerVolumesSystem`
+///
+/// # Example
+///
+/// ```rust
+/// use game_physics::{collider::CollisionGroups, TriggerVolume};
+///
+/// let trigger_volume = TriggerVolume::default()
+///     .with_filter(CollisionGroups::new().with_membership(&[1]));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct TriggerVolume {
+    filter: Option<CollisionGroups>,
+    pub(crate) entities: HashSet<Entity>,
+}
+
+impl Component for TriggerVolume {
+    type Storage = FlaggedStorage<Self, DenseVecStorage<Self>>;
+}
+
+impl TriggerVolume {
+    /// Only tracks `Entity`s whose `Collider` `CollisionGroups` can interact
+    /// with the given `CollisionGroups`.
+    pub fn with_filter(mut self, filter: CollisionGroups) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Returns all `Entity`s currently overlapping the `TriggerVolume`.
+    pub fn entities(&self) -> &HashSet<Entity> {
+        &self.entities
+    }
+
+    /// Checks whether the given `Entity` currently overlaps the
+    /// `TriggerVolume`.
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.entities.contains(&entity)
+    }
+
+    /// Checks whether an `Entity` with the given `CollisionGroups` passes the
+    /// filter of the `TriggerVolume`.
+    pub(crate) fn accepts(&self, collision_groups: &CollisionGroups) -> bool {
+        self.filter
+            .as_ref()
+            .map_or(true, |filter| filter.can_interact_with(collision_groups))
+    }
+}
+
+/// The `TriggerEvent`s published whenever an `Entity` enters or leaves a
+/// `TriggerVolume`. An `TriggerEvent::Exit` is also published when either of
+/// both `Entity`s is deleted or loses its `PhysicsCollider` while they
+/// overlap.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TriggerEvent {
+    Enter { trigger: Entity, entity: Entity },
+    Exit { trigger: Entity, entity: Entity },
+}
+
+/// Custom type alias for `EventChannel<TriggerEvent>`.
+pub type TriggerEventChannel = EventChannel<TriggerEvent>;
@@ -0,0 +1,64 @@
+use std::{fmt, hash::Hasher};
+
+use crate::math::{self, RealField};
+
+/// The `PhysicsWorldHash` `Resource` is a fingerprint of the state of all
+/// `RigidBody`s in the `PhysicsWorld`, recomputed after each step. Two runs of
+/// the same sequence of commands in deterministic mode produce the same hash
+/// after every step, which allows lockstep peers and replays to detect
+/// diverging simulations cheaply.
+///
+/// The `PhysicsWorldHash` is only maintained if the deterministic mode is
+/// enabled; see `PhysicsBundle::deterministic`.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct PhysicsWorldHash {
+    /// The number of steps the hash was computed after.
+    pub step: u64,
+    /// The hash of the position, velocity, status and activation of every
+    /// `RigidBody` after the step.
+    pub hash: u64,
+}
+
+impl fmt::Display for PhysicsWorldHash {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "step {}: {:016x}", self.step, self.hash)
+    }
+}
+
+/// The `WorldHasher` implements the 64 bit FNV-1a hash. Unlike the
+/// `DefaultHasher` of the standard library, its output is specified and
+/// stable across Rust releases.
+pub(crate) struct WorldHasher(u64);
+
+impl WorldHasher {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    pub(crate) fn new() -> Self {
+        WorldHasher(Self::OFFSET_BASIS)
+    }
+
+    /// Writes the exact bit pattern of a scalar, so that any difference in the
+    /// simulation changes the hash.
+    pub(crate) fn write_scalar<N: RealField>(&mut self, value: N) {
+        let value = math::try_convert::<N, f64>(value).unwrap_or(0.0);
+        self.write_u64(value.to_bits());
+    }
+}
+
+impl Hasher for WorldHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+}
@@ -0,0 +1,157 @@
+//! Integration tests for the continuous collision detection of fast
+//! `PhysicsBody`s, run on the headless `PhysicsHarness`.
+//!
+//! ```bash
+//! $ cargo test --features test-utils
+//! ```
+
+use amethyst::ecs::Entity;
+use game_physics::{
+    body::BodyStatus,
+    test_utils::PhysicsHarness,
+    PhysicsBodyBuilder,
+    PhysicsColliderBuilder,
+    Shape,
+    Vector,
+};
+
+/// Creates a static obstacle of the given size at the given position.
+fn spawn_obstacle(harness: &mut PhysicsHarness<f32>, size: Vector<f32>, position: Vector<f32>) {
+    harness.spawn_collider(
+        PhysicsColliderBuilder::from(Shape::Rectangle(size.x, size.y, size.z)).margin(0.01),
+        position,
+    );
+}
+
+/// Creates a small projectile at the given position that moves with the given
+/// velocity per step, optionally with CCD.
+fn spawn_projectile(
+    harness: &mut PhysicsHarness<f32>,
+    position: Vector<f32>,
+    velocity: Vector<f32>,
+    ccd: bool,
+) -> Entity {
+    let mut physics_body = PhysicsBodyBuilder::from(BodyStatus::Dynamic)
+        .velocity(velocity)
+        .mass(1.0);
+    if ccd {
+        physics_body = physics_body.ccd(0.5);
+    }
+
+    harness.spawn_body_with_collider(
+        physics_body,
+        PhysicsColliderBuilder::from(Shape::Circle(0.5)).margin(0.01),
+        position,
+    )
+}
+
+/// Creates a thin static wall at x = 10 and a small projectile at x = 0.9 that
+/// moves 2 units per step towards the wall. Without CCD, the projectile is
+/// never overlapping the wall at the end of any step.
+fn setup(ccd: bool) -> (PhysicsHarness<f32>, Entity) {
+    let mut harness = PhysicsHarness::new();
+    spawn_obstacle(
+        &mut harness,
+        Vector::new(0.2, 10.0, 10.0),
+        Vector::new(10.0, 0.0, 0.0),
+    );
+    let projectile = spawn_projectile(
+        &mut harness,
+        Vector::new(0.9, 0.0, 0.0),
+        Vector::new(2.0, 0.0, 0.0),
+        ccd,
+    );
+    (harness, projectile)
+}
+
+#[test]
+fn fast_body_tunnels_without_ccd() {
+    let (mut harness, projectile) = setup(false);
+
+    harness.run(20);
+
+    assert!(harness.translation(projectile).unwrap().x > 10.0);
+}
+
+#[test]
+fn fast_body_stops_at_thin_wall_with_ccd() {
+    let (mut harness, projectile) = setup(true);
+
+    harness.run(20);
+
+    let x = harness.translation(projectile).unwrap().x;
+    assert!(x < 10.0, "projectile tunnelled through the wall: x = {}", x);
+    assert!(x > 8.0, "projectile stopped too early: x = {}", x);
+}
+
+#[test]
+fn fast_body_far_from_wall_is_not_clamped() {
+    let (mut harness, projectile) = setup(true);
+
+    // a single step moves the projectile by 2 units, which is above the threshold,
+    // but the wall is out of reach, so the motion is left untouched
+    harness.step();
+
+    assert!((harness.translation(projectile).unwrap().x - 2.9).abs() < 0.01);
+}
+
+#[test]
+fn fast_body_stops_at_off_centre_obstacle_with_ccd() {
+    let mut harness = PhysicsHarness::new();
+
+    // the obstacle only overlaps the upper half of the projectile, so a single ray
+    // from its centre would miss it
+    spawn_obstacle(
+        &mut harness,
+        Vector::new(0.2, 0.4, 10.0),
+        Vector::new(10.0, 0.4, 0.0),
+    );
+    let projectile = spawn_projectile(
+        &mut harness,
+        Vector::new(0.9, 0.0, 0.0),
+        Vector::new(2.0, 0.0, 0.0),
+        true,
+    );
+
+    harness.run(20);
+
+    let x = harness.translation(projectile).unwrap().x;
+    assert!(
+        x < 10.0,
+        "projectile tunnelled through the obstacle: x = {}",
+        x
+    );
+}
+
+#[test]
+fn fast_body_keeps_sliding_along_obstacle_with_ccd() {
+    let mut harness = PhysicsHarness::new();
+
+    // a thin floor below the projectile, which moves diagonally towards it
+    spawn_obstacle(
+        &mut harness,
+        Vector::new(100.0, 0.2, 10.0),
+        Vector::new(0.0, -1.0, 0.0),
+    );
+    let projectile = spawn_projectile(
+        &mut harness,
+        Vector::new(0.9, 0.0, 0.0),
+        Vector::new(2.0, -2.0, 0.0),
+        true,
+    );
+
+    harness.step();
+
+    // only the motion towards the floor is clamped, the motion along it is kept
+    let translation = harness.translation(projectile).unwrap();
+    assert!(
+        translation.x > 2.8,
+        "projectile lost its tangential motion: x = {}",
+        translation.x
+    );
+    assert!(
+        translation.y > -1.0,
+        "projectile tunnelled through the floor: y = {}",
+        translation.y
+    );
+}
@@ -0,0 +1,166 @@
+//! Integration tests for the deterministic mode of the `PhysicsBundle`.
+//!
+//! ```bash
+//! $ cargo test --features test-utils
+//! ```
+
+use amethyst::ecs::{Builder, Entity};
+use game_physics::{
+    body::BodyStatus,
+    test_utils::{transform_at, PhysicsHarness},
+    PhysicsBody,
+    PhysicsBodyBuilder,
+    PhysicsBundle,
+    PhysicsColliderBuilder,
+    PhysicsWorldHash,
+    Shape,
+    Tile,
+    TileGrid,
+    Vector,
+};
+
+/// A single command applied to the simulation before a given step.
+#[derive(Clone, Copy, Debug)]
+enum Command {
+    /// Spawns a dynamic ball at the given position.
+    SpawnBall(f32, f32),
+    /// Deletes the nth spawned ball.
+    DeleteBall(usize),
+    /// Pushes the nth spawned ball along the x axis.
+    PushBall(usize, f32),
+    /// Sets a tile of the TileGrid to solid.
+    SetTile(usize, usize),
+}
+
+/// Creates a deterministic `PhysicsHarness` with gravity, a static floor and
+/// a `TileGrid` to drop the balls onto. Returns the `Entity` of the
+/// `TileGrid` along with the `PhysicsHarness`.
+fn setup() -> (PhysicsHarness<f32>, Entity) {
+    let mut harness = PhysicsHarness::from_bundle(PhysicsBundle::default().deterministic())
+        .with_gravity(Vector::new(0.0, -10.0, 0.0));
+
+    harness.spawn_collider(
+        PhysicsColliderBuilder::from(Shape::Rectangle(40.0, 1.0, 40.0)),
+        Vector::new(0.0, -1.0, 0.0),
+    );
+    let tile_grid = harness
+        .create_entity()
+        .with(
+            TileGrid::new(
+                8,
+                2,
+                1.0,
+                1.0,
+                PhysicsColliderBuilder::from(Shape::Rectangle(1.0, 1.0, 1.0)),
+            )
+            .with_tiles(vec![Tile::Solid; 8].into_iter().chain(vec![Tile::Empty; 8])),
+        )
+        .with(transform_at(&Vector::new(4.0, 0.0, 0.0)))
+        .build();
+
+    (harness, tile_grid)
+}
+
+/// Runs the given commands, each applied before its step, and returns the
+/// `PhysicsWorldHash` after every step.
+fn simulate(steps: usize, commands: &[(usize, Command)]) -> Vec<PhysicsWorldHash> {
+    let (mut harness, tile_grid) = setup();
+    let mut balls: Vec<Entity> = Vec::new();
+    let mut hashes = Vec::new();
+
+    for step in 0..steps {
+        for (_, command) in commands.iter().filter(|(at, _)| *at == step) {
+            match *command {
+                Command::SpawnBall(x, y) => balls.push(
+                    harness.spawn_body_with_collider(
+                        PhysicsBodyBuilder::from(BodyStatus::Dynamic)
+                            .gravity_enabled(true)
+                            .mass(1.0),
+                        PhysicsColliderBuilder::from(Shape::Circle(0.5)),
+                        Vector::new(x, y, 0.0),
+                    ),
+                ),
+                Command::DeleteBall(n) => harness.delete(balls[n]),
+                Command::PushBall(n, velocity) => {
+                    harness.modify(balls[n], |physics_body: &mut PhysicsBody<f32>| {
+                        physics_body.velocity.x = velocity;
+                    })
+                }
+                Command::SetTile(x, y) => {
+                    harness.modify(tile_grid, |tile_grid: &mut TileGrid<f32>| {
+                        tile_grid.set_tile(x, y, Tile::Solid);
+                    })
+                }
+            }
+        }
+
+        harness.step();
+        hashes.push(harness.world_hash().expect("PhysicsWorldHash missing"));
+    }
+
+    hashes
+}
+
+/// A sequence of commands that keeps the balls colliding with each other, the
+/// floor and the `TileGrid`.
+fn commands() -> Vec<(usize, Command)> {
+    let mut commands = Vec::new();
+    for i in 0..20 {
+        commands.push((
+            0,
+            Command::SpawnBall((i % 5) as f32 * 0.9, 2.0 + (i / 5) as f32),
+        ));
+    }
+    commands.push((30, Command::PushBall(3, 0.2)));
+    commands.push((45, Command::SetTile(2, 1)));
+    commands.push((60, Command::DeleteBall(7)));
+    commands.push((60, Command::SpawnBall(6.0, 5.0)));
+    commands.push((90, Command::DeleteBall(0)));
+    commands.push((90, Command::SpawnBall(1.0, 6.0)));
+    commands
+}
+
+#[test]
+fn identical_commands_produce_identical_hashes() {
+    let commands = commands();
+
+    let first = simulate(150, &commands);
+    let second = simulate(150, &commands);
+
+    assert_eq!(first, second);
+}
+
+#[test]
+fn different_commands_produce_different_hashes() {
+    let commands = commands();
+    let mut changed = commands.clone();
+    changed.push((30, Command::PushBall(4, 0.2)));
+
+    let first = simulate(60, &commands);
+    let second = simulate(60, &changed);
+
+    // the runs are identical until the changed command is applied
+    assert_eq!(first[..30], second[..30]);
+    assert_ne!(first[59], second[59]);
+}
+
+#[test]
+fn hash_is_updated_after_every_step() {
+    let hashes = simulate(20, &commands());
+
+    for (step, hash) in hashes.iter().enumerate() {
+        assert_eq!(hash.step, step as u64 + 1);
+    }
+    // the falling balls change the state with every step
+    for pair in hashes.windows(2) {
+        assert_ne!(pair[0].hash, pair[1].hash);
+    }
+}
+
+#[test]
+fn hash_is_not_maintained_without_deterministic_mode() {
+    let mut harness = PhysicsHarness::<f32>::new();
+    harness.step();
+
+    assert!(harness.world_hash().is_none());
+}
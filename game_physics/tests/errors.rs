@@ -0,0 +1,245 @@
+//! Integration tests for the `PhysicsError`s reported instead of panicking
+//! on invalid `Entity`s, run on the headless `PhysicsHarness`.
+//!
+//! ```bash
+//! $ cargo test --features test-utils
+//! ```
+
+use amethyst::{
+    core::{Parent, Transform},
+    ecs::{Builder, Entity, World},
+};
+use game_physics::{
+    body::BodyStatus,
+    test_utils::PhysicsHarness,
+    PhysicsBody,
+    PhysicsBodyBuilder,
+    PhysicsBundle,
+    PhysicsColliderBuilder,
+    PhysicsError,
+    PhysicsWorld,
+    Shape,
+};
+
+fn dynamic_body() -> PhysicsBody<f32> {
+    PhysicsBodyBuilder::from(BodyStatus::Dynamic).build()
+}
+
+fn ball() -> PhysicsColliderBuilder<f32> {
+    PhysicsColliderBuilder::from(Shape::Circle(0.5))
+}
+
+/// Removes the `RigidBody` of the given `Entity` from the `PhysicsWorld`
+/// without removing its `PhysicsBody`.
+fn remove_rigid_body(harness: &PhysicsHarness<f32>, entity: Entity) {
+    let handle = harness.body_handle(entity).unwrap();
+    harness
+        .world
+        .write_resource::<PhysicsWorld<f32>>()
+        .remove_bodies(&[handle]);
+}
+
+/// Counts the `PhysicsError`s equal to the given one.
+fn count(errors: &[PhysicsError], error: PhysicsError) -> usize {
+    errors.iter().filter(|other| **other == error).count()
+}
+
+#[test]
+fn body_without_transform_reports_missing_transform() {
+    let mut harness = PhysicsHarness::<f32>::new();
+    let mut reader_id = harness.register_reader::<PhysicsError>();
+    let entity = harness.create_entity().with(dynamic_body()).build();
+
+    harness.step();
+
+    let errors = harness.read_events(&mut reader_id);
+    assert!(errors.contains(&PhysicsError::MissingTransform { entity }));
+}
+
+#[test]
+fn collider_without_transform_reports_missing_transform() {
+    let mut harness = PhysicsHarness::<f32>::new();
+    let mut reader_id = harness.register_reader::<PhysicsError>();
+    let entity = harness.create_entity().with(ball().build()).build();
+
+    harness.step();
+
+    let errors = harness.read_events(&mut reader_id);
+    assert!(errors.contains(&PhysicsError::MissingTransform { entity }));
+}
+
+#[test]
+fn pending_body_reports_missing_transform_once() {
+    let mut harness = PhysicsHarness::<f32>::new();
+    let mut reader_id = harness.register_reader::<PhysicsError>();
+    let entity = harness.create_entity().with(dynamic_body()).build();
+
+    harness.step();
+    let errors = harness.read_events(&mut reader_id);
+    assert!(errors.contains(&PhysicsError::MissingTransform { entity }));
+    harness.step();
+    let errors = harness.read_events(&mut reader_id);
+    assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+
+    harness.insert(entity, Transform::default());
+    harness.step();
+
+    let errors = harness.read_events(&mut reader_id);
+    assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+    assert!(harness.body_handle(entity).is_some());
+}
+
+#[test]
+fn pending_collider_reports_missing_transform_once() {
+    let mut harness = PhysicsHarness::<f32>::new();
+    let mut reader_id = harness.register_reader::<PhysicsError>();
+    let entity = harness.create_entity().with(ball().build()).build();
+
+    harness.step();
+    let errors = harness.read_events(&mut reader_id);
+    assert!(errors.contains(&PhysicsError::MissingTransform { entity }));
+    harness.step();
+    let errors = harness.read_events(&mut reader_id);
+    assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+}
+
+#[test]
+fn repeatedly_pending_body_reports_missing_transform_again() {
+    let mut harness = PhysicsHarness::<f32>::new();
+    let mut reader_id = harness.register_reader::<PhysicsError>();
+    let entity = harness.create_entity().with(dynamic_body()).build();
+    harness.step();
+
+    // a removed PhysicsBody is no longer pending
+    harness.remove::<PhysicsBody<f32>>(entity);
+    harness.step();
+    harness.insert(entity, dynamic_body());
+    harness.read_events(&mut reader_id);
+    harness.step();
+
+    let errors = harness.read_events(&mut reader_id);
+    assert!(errors.contains(&PhysicsError::MissingTransform { entity }));
+}
+
+#[test]
+fn body_without_rigid_body_reports_missing_rigid_body() {
+    let mut harness = PhysicsHarness::<f32>::new();
+    let mut reader_id = harness.register_reader::<PhysicsError>();
+    let entity = harness
+        .create_entity()
+        .with(dynamic_body())
+        .with(Transform::default())
+        .build();
+    harness.step();
+    remove_rigid_body(&harness, entity);
+    harness.read_events(&mut reader_id);
+
+    // the position of the RigidBody cannot be synchronised
+    harness.step();
+    let errors = harness.read_events(&mut reader_id);
+    assert_eq!(
+        count(&errors, PhysicsError::MissingRigidBody { entity }),
+        1,
+        "{:?}",
+        errors
+    );
+
+    // neither can the modified PhysicsBody
+    harness.modify(entity, |physics_body: &mut PhysicsBody<f32>| {
+        physics_body.gravity_enabled = false;
+    });
+    harness.step();
+    let errors = harness.read_events(&mut reader_id);
+    assert_eq!(
+        count(&errors, PhysicsError::MissingRigidBody { entity }),
+        2,
+        "{:?}",
+        errors
+    );
+}
+
+#[test]
+fn collider_of_pending_body_reports_missing_rigid_body() {
+    let mut harness = PhysicsHarness::<f32>::new();
+    let mut reader_id = harness.register_reader::<PhysicsError>();
+    let body = harness.create_entity().with(dynamic_body()).build();
+    harness
+        .create_entity()
+        .with(ball().build())
+        .with(Transform::default())
+        .with(Parent { entity: body })
+        .build();
+
+    harness.step();
+
+    let errors = harness.read_events(&mut reader_id);
+    assert!(errors.contains(&PhysicsError::MissingTransform { entity: body }));
+    assert!(errors.contains(&PhysicsError::MissingRigidBody { entity: body }));
+}
+
+#[test]
+fn collider_of_removed_rigid_body_reports_collider_creation() {
+    let mut harness = PhysicsHarness::<f32>::new();
+    let mut reader_id = harness.register_reader::<PhysicsError>();
+    let body = harness
+        .create_entity()
+        .with(dynamic_body())
+        .with(Transform::default())
+        .build();
+    harness.step();
+    remove_rigid_body(&harness, body);
+
+    let entity = harness
+        .create_entity()
+        .with(ball().build())
+        .with(Transform::default())
+        .with(Parent { entity: body })
+        .build();
+    harness.step();
+
+    let errors = harness.read_events(&mut reader_id);
+    assert!(errors.contains(&PhysicsError::ColliderCreation { entity }));
+}
+
+#[test]
+fn debug_lines_report_missing_collider() {
+    let mut harness =
+        PhysicsHarness::<f32>::from_bundle(PhysicsBundle::default().with_debug_lines());
+    let mut reader_id = harness.register_reader::<PhysicsError>();
+    let entity = harness
+        .create_entity()
+        .with(PhysicsColliderBuilder::<f32>::from(Shape::Rectangle(1.0, 1.0, 1.0)).build())
+        .build();
+
+    harness.step();
+
+    let errors = harness.read_events(&mut reader_id);
+    assert!(errors.contains(&PhysicsError::MissingCollider { entity }));
+}
+
+#[test]
+fn valid_entities_report_no_errors() {
+    let mut harness = PhysicsHarness::<f32>::new();
+    let mut reader_id = harness.register_reader::<PhysicsError>();
+    harness
+        .create_entity()
+        .with(dynamic_body())
+        .with(ball().build())
+        .with(Transform::default())
+        .build();
+
+    harness.step();
+
+    let errors = harness.read_events(&mut reader_id);
+    assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+}
+
+#[test]
+fn errors_refer_to_their_entity() {
+    let world = World::new();
+    let entity = world.entities().create();
+    let error = PhysicsError::MissingCollider { entity };
+
+    assert_eq!(error.entity(), entity);
+    assert!(error.to_string().contains("Collider"));
+}
@@ -0,0 +1,171 @@
+//! Integration tests for the creation of `RigidBody`s and `Collider`s
+//! regardless of the order their `Component`s are inserted in, run on the
+//! headless `PhysicsHarness`.
+//!
+//! ```bash
+//! $ cargo test --features test-utils
+//! ```
+
+use amethyst::{
+    core::{Parent, Transform},
+    ecs::{Builder, Entity},
+};
+use game_physics::{
+    body::BodyStatus,
+    test_utils::{transform_at, PhysicsHarness},
+    PhysicsBodyBuilder,
+    PhysicsColliderBuilder,
+    PhysicsError,
+    PhysicsWorld,
+    Shape,
+    Vector,
+};
+
+/// The `Component`s inserted one frame at a time.
+#[derive(Clone, Copy, Debug)]
+enum Insertion {
+    Transform,
+    PhysicsBody,
+    PhysicsCollider,
+    Parent,
+}
+
+const INSERTIONS: [Insertion; 4] = [
+    Insertion::Transform,
+    Insertion::PhysicsBody,
+    Insertion::PhysicsCollider,
+    Insertion::Parent,
+];
+
+/// Returns all permutations of the given `Insertion`s.
+fn permutations(insertions: &[Insertion]) -> Vec<Vec<Insertion>> {
+    if insertions.len() <= 1 {
+        return vec![insertions.to_vec()];
+    }
+
+    let mut permutations = Vec::new();
+    for (i, insertion) in insertions.iter().enumerate() {
+        let mut rest = insertions.to_vec();
+        rest.remove(i);
+        for mut permutation in self::permutations(&rest) {
+            permutation.insert(0, *insertion);
+            permutations.push(permutation);
+        }
+    }
+    permutations
+}
+
+/// Inserts a single `Component`. The `PhysicsBody` and `Transform` go to the
+/// `body` `Entity`, the `PhysicsCollider` goes to the `collider` `Entity` and
+/// the `Parent` links the `child` to the `parent` `Entity`.
+fn insert(
+    harness: &mut PhysicsHarness<f32>,
+    insertion: Insertion,
+    body: Entity,
+    collider: Entity,
+    (child, parent): (Entity, Entity),
+) {
+    match insertion {
+        Insertion::Transform => {
+            harness.insert(body, transform_at(&Vector::new(1.0, 0.0, 0.0)));
+        }
+        Insertion::PhysicsBody => {
+            harness.insert(
+                body,
+                PhysicsBodyBuilder::<f32>::from(BodyStatus::Kinematic).build(),
+            );
+        }
+        Insertion::PhysicsCollider => {
+            harness.insert(
+                collider,
+                PhysicsColliderBuilder::<f32>::from(Shape::Circle(0.5)).build(),
+            );
+        }
+        Insertion::Parent => {
+            harness.insert(child, Parent { entity: parent });
+        }
+    }
+}
+
+/// Asserts that the `RigidBody` of `body` exists at x = 1 and that the
+/// `Collider` of `collider` is attached to it.
+fn assert_attached(
+    harness: &PhysicsHarness<f32>,
+    body: Entity,
+    collider: Entity,
+    order: &[Insertion],
+) {
+    let physics_world = harness.world.read_resource::<PhysicsWorld<f32>>();
+    let body_handle = harness
+        .body_handle(body)
+        .unwrap_or_else(|| panic!("no RigidBody for {:?}", order));
+    let collider_handle = harness
+        .collider_handle(collider)
+        .unwrap_or_else(|| panic!("no Collider for {:?}", order));
+
+    let rigid_body = physics_world.rigid_body(body_handle).unwrap();
+    assert!(
+        (rigid_body.position().translation.vector.x - 1.0).abs() < 0.001,
+        "RigidBody misplaced for {:?}",
+        order
+    );
+    assert_eq!(
+        physics_world.collider(collider_handle).unwrap().body(),
+        body_handle,
+        "Collider not attached for {:?}",
+        order
+    );
+}
+
+/// Inserts the `Component`s in the given order, one frame at a time, and
+/// returns the number of `PhysicsError`s reported in the final frame.
+fn run_insertions(
+    harness: &mut PhysicsHarness<f32>,
+    order: &[Insertion],
+    body: Entity,
+    collider: Entity,
+    link: (Entity, Entity),
+) -> usize {
+    let mut reader_id = harness.register_reader::<PhysicsError>();
+    for insertion in order {
+        insert(harness, *insertion, body, collider, link);
+        harness.step();
+    }
+
+    // drain the errors of the insertion frames
+    harness.read_events(&mut reader_id);
+    harness.step();
+    harness.read_events(&mut reader_id).len()
+}
+
+#[test]
+fn body_and_collider_on_child_entity() {
+    for order in permutations(&INSERTIONS) {
+        let mut harness = PhysicsHarness::<f32>::new();
+
+        // the parent sits at the origin, so the Parent does not move the child
+        let parent = harness.create_entity().with(Transform::default()).build();
+        let child = harness.create_entity().build();
+
+        let errors = run_insertions(&mut harness, &order, child, child, (child, parent));
+
+        assert_attached(&harness, child, child, &order);
+        assert_eq!(errors, 0, "unexpected errors for {:?}", order);
+    }
+}
+
+#[test]
+fn collider_on_child_of_body_entity() {
+    for order in permutations(&INSERTIONS) {
+        let mut harness = PhysicsHarness::<f32>::new();
+
+        // the child collider sits at the same position as its parent body
+        let body = harness.create_entity().build();
+        let collider = harness.create_entity().with(Transform::default()).build();
+
+        let errors = run_insertions(&mut harness, &order, body, collider, (collider, body));
+
+        assert_attached(&harness, body, collider, &order);
+        assert_eq!(errors, 0, "unexpected errors for {:?}", order);
+    }
+}
@@ -0,0 +1,456 @@
+//! Integration tests for the synchronisation of the `PhysicsBundle`, run on
+//! the headless `PhysicsHarness`.
+//!
+//! ```bash
+//! $ cargo test --features test-utils
+//! ```
+
+use std::{
+    f32::consts::FRAC_PI_2,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use amethyst::{
+    core::{math::Vector3, Parent, Transform},
+    ecs::Entity,
+};
+use game_physics::{
+    body::BodyStatus,
+    test_utils::{transform_at, PhysicsHarness},
+    ContactFilterPair,
+    Contacts,
+    GravityZone,
+    GroundState,
+    PhysicsBody,
+    PhysicsBodyBuilder,
+    PhysicsBundle,
+    PhysicsCollider,
+    PhysicsColliderBuilder,
+    PhysicsWorld,
+    Shape,
+    TriggerEvent,
+    TriggerVolume,
+    Vector,
+};
+
+const EPSILON: f32 = 0.001;
+
+fn dynamic_body() -> PhysicsBodyBuilder<f32> {
+    PhysicsBodyBuilder::from(BodyStatus::Dynamic).mass(1.0)
+}
+
+fn ball() -> PhysicsColliderBuilder<f32> {
+    PhysicsColliderBuilder::from(Shape::Circle(0.5))
+}
+
+#[test]
+fn inserted_body_is_created_at_its_transform() {
+    let mut harness = PhysicsHarness::<f32>::new();
+    let entity = harness.spawn_body(dynamic_body(), Vector::new(1.0, 2.0, 3.0));
+
+    harness.step();
+
+    let position = harness.body_position(entity).unwrap();
+    assert!((position.translation.vector - Vector::new(1.0, 2.0, 3.0)).norm() < EPSILON);
+    harness.assert_translation(entity, Vector::new(1.0, 2.0, 3.0), EPSILON);
+}
+
+#[test]
+fn modified_body_velocity_moves_transform() {
+    let mut harness = PhysicsHarness::<f32>::new();
+    let entity = harness.spawn_body(dynamic_body(), Vector::zeros());
+    harness.step();
+
+    // the velocity of a PhysicsBody is given per step
+    harness.modify(entity, |physics_body: &mut PhysicsBody<f32>| {
+        physics_body.velocity = Vector::new(0.1, 0.0, 0.0);
+    });
+    harness.run(10);
+
+    harness.assert_translation(entity, Vector::new(1.0, 0.0, 0.0), 0.01);
+}
+
+#[test]
+fn modified_transform_repositions_body_on_body_update() {
+    let mut harness = PhysicsHarness::<f32>::new();
+    let entity = harness.spawn_body(
+        PhysicsBodyBuilder::from(BodyStatus::Kinematic),
+        Vector::zeros(),
+    );
+    harness.step();
+
+    harness.insert(entity, transform_at(&Vector::new(5.0, 0.0, 0.0)));
+    harness.modify(entity, |_: &mut PhysicsBody<f32>| {});
+    harness.step();
+
+    let position = harness.body_position(entity).unwrap();
+    assert!((position.translation.vector.x - 5.0).abs() < EPSILON);
+}
+
+#[test]
+fn removed_body_is_removed_from_world() {
+    let mut harness = PhysicsHarness::<f32>::new();
+    let entity = harness.spawn_body_with_collider(dynamic_body(), ball(), Vector::zeros());
+    harness.step();
+    assert!(harness.body_handle(entity).is_some());
+
+    harness.remove::<PhysicsBody<f32>>(entity);
+    harness.step();
+
+    assert!(harness.body_handle(entity).is_none());
+    // the Collider is rebuilt on the ground once its RigidBody is gone
+    assert!(harness.has_collider(entity));
+    assert!(harness.collider_body(entity).unwrap().is_ground());
+}
+
+#[test]
+fn deleted_entity_removes_body_and_collider() {
+    let mut harness = PhysicsHarness::<f32>::new();
+    let entity = harness.spawn_body_with_collider(dynamic_body(), ball(), Vector::zeros());
+    harness.step();
+
+    harness.delete(entity);
+    harness.step();
+
+    assert!(harness.body_handle(entity).is_none());
+    assert!(harness.collider_handle(entity).is_none());
+}
+
+#[test]
+fn inserted_collider_is_attached_to_body_of_same_entity() {
+    let mut harness = PhysicsHarness::<f32>::new();
+    let entity = harness.spawn_body_with_collider(dynamic_body(), ball(), Vector::zeros());
+
+    harness.step();
+
+    assert_eq!(harness.collider_body(entity), harness.body_handle(entity));
+}
+
+#[test]
+fn collider_without_body_is_static() {
+    let mut harness = PhysicsHarness::<f32>::new();
+    let entity = harness.spawn_collider(ball(), Vector::zeros());
+
+    harness.step();
+
+    assert!(harness.collider_body(entity).unwrap().is_ground());
+}
+
+#[test]
+fn modified_collider_keeps_its_collider() {
+    let mut harness = PhysicsHarness::<f32>::new();
+    let entity = harness.spawn_collider(ball(), Vector::zeros());
+    harness.step();
+    let handle = harness.collider_handle(entity);
+
+    harness.modify(entity, |physics_collider: &mut PhysicsCollider<f32>| {
+        physics_collider.layer = Some("ground".to_string());
+    });
+    harness.step();
+
+    assert_eq!(harness.collider_handle(entity), handle);
+    assert!(harness.has_collider(entity));
+}
+
+#[test]
+fn rescaled_collider_is_rebuilt() {
+    let mut harness = PhysicsHarness::<f32>::new();
+    let entity = harness.spawn_collider(ball(), Vector::zeros());
+    harness.step();
+
+    let mut transform = Transform::default();
+    transform.set_scale(Vector3::new(2.0, 2.0, 2.0));
+    harness.insert(entity, transform);
+    harness.step();
+
+    assert!(harness.has_collider(entity));
+    assert_eq!(
+        harness
+            .world
+            .read_storage::<PhysicsCollider<f32>>()
+            .get(entity)
+            .unwrap()
+            .scaled_shape(),
+        Shape::Circle(1.0)
+    );
+}
+
+#[test]
+fn removed_collider_is_removed_from_world() {
+    let mut harness = PhysicsHarness::<f32>::new();
+    let entity = harness.spawn_collider(ball(), Vector::zeros());
+    harness.step();
+
+    harness.remove::<PhysicsCollider<f32>>(entity);
+    harness.step();
+
+    assert!(harness.collider_handle(entity).is_none());
+}
+
+#[test]
+fn gravity_accelerates_gravity_enabled_bodies() {
+    let mut harness = PhysicsHarness::<f32>::new().with_gravity(Vector::new(0.0, -10.0, 0.0));
+    let falling = harness.spawn_body(dynamic_body().gravity_enabled(true), Vector::zeros());
+    let floating = harness.spawn_body(
+        dynamic_body().gravity_enabled(false),
+        Vector::new(5.0, 0.0, 0.0),
+    );
+
+    harness.run(60);
+
+    // roughly 0.5 * g * t^2 after one second
+    let y = harness.translation(falling).unwrap().y;
+    assert!(y < -4.0 && y > -6.0, "unexpected fall distance: y = {}", y);
+    harness.assert_translation(floating, Vector::new(5.0, 0.0, 0.0), EPSILON);
+}
+
+#[test]
+fn sensor_reports_overlaps_without_blocking() {
+    let mut harness = PhysicsHarness::<f32>::new().with_gravity(Vector::new(0.0, -10.0, 0.0));
+    let mut reader_id = harness.register_reader::<TriggerEvent>();
+
+    let sensor = harness.spawn_collider(
+        PhysicsColliderBuilder::from(Shape::Rectangle(4.0, 1.0, 4.0)).sensor(true),
+        Vector::new(0.0, -2.0, 0.0),
+    );
+    harness.insert(sensor, TriggerVolume::default());
+    let body = harness.spawn_body_with_collider(
+        dynamic_body().gravity_enabled(true),
+        ball(),
+        Vector::zeros(),
+    );
+
+    harness.run(90);
+
+    let events = harness.read_events(&mut reader_id);
+    assert!(events.contains(&TriggerEvent::Enter {
+        trigger: sensor,
+        entity: body,
+    }));
+    assert!(harness.translation(body).unwrap().y < -4.0);
+}
+
+#[test]
+fn trigger_volume_inserted_during_overlap_sees_entity() {
+    let mut harness = PhysicsHarness::<f32>::new();
+    let mut reader_id = harness.register_reader::<TriggerEvent>();
+
+    let sensor = harness.spawn_collider(
+        PhysicsColliderBuilder::from(Shape::Rectangle(4.0, 4.0, 4.0)).sensor(true),
+        Vector::zeros(),
+    );
+    let body = harness.spawn_body_with_collider(dynamic_body(), ball(), Vector::zeros());
+    harness.run(2);
+
+    // the proximity is already in progress, no further proximity event is published
+    harness.insert(sensor, TriggerVolume::default());
+    harness.step();
+
+    let events = harness.read_events(&mut reader_id);
+    assert!(events.contains(&TriggerEvent::Enter {
+        trigger: sensor,
+        entity: body,
+    }));
+    let trigger_volumes = harness.world.read_storage::<TriggerVolume>();
+    assert!(trigger_volumes.get(sensor).unwrap().contains(body));
+}
+
+#[test]
+fn child_collider_moves_with_parent_body() {
+    let mut harness = PhysicsHarness::<f32>::new();
+    let body = harness.spawn_body(
+        dynamic_body().velocity(Vector::new(0.1, 0.0, 0.0)),
+        Vector::zeros(),
+    );
+    let child = harness.spawn_child_collider(body, ball(), Vector::new(0.0, 1.0, 0.0));
+
+    harness.run(10);
+
+    assert_eq!(harness.collider_body(child), harness.body_handle(body));
+    harness.assert_translation(child, Vector::new(0.0, 1.0, 0.0), EPSILON);
+    let world_translation = harness.world_translation(child).unwrap();
+    assert!((world_translation - Vector::new(1.0, 1.0, 0.0)).norm() < 0.01);
+}
+
+/// Creates a `Transform` at the given translation, rotated by 90 degrees
+/// around the z axis.
+fn rotated_transform_at(translation: &Vector<f32>) -> Transform {
+    let mut transform = transform_at(translation);
+    transform.set_rotation_euler(0.0.into(), 0.0.into(), FRAC_PI_2.into());
+    transform
+}
+
+#[test]
+fn inserted_body_is_created_with_its_rotation() {
+    let mut harness = PhysicsHarness::<f32>::new();
+    let entity = harness
+        .create_entity()
+        .with(PhysicsBodyBuilder::<f32>::from(BodyStatus::Kinematic).build())
+        .with(rotated_transform_at(&Vector::new(1.0, 0.0, 0.0)))
+        .build();
+
+    harness.step();
+
+    let position = harness.body_position(entity).unwrap();
+    assert!((position.rotation.angle() - FRAC_PI_2).abs() < EPSILON);
+    assert!((position.translation.vector - Vector::new(1.0, 0.0, 0.0)).norm() < EPSILON);
+}
+
+#[test]
+fn child_collider_is_placed_relative_to_rotated_parent_body() {
+    let mut harness = PhysicsHarness::<f32>::new();
+    let body = harness
+        .create_entity()
+        .with(PhysicsBodyBuilder::<f32>::from(BodyStatus::Kinematic).build())
+        .with(rotated_transform_at(&Vector::zeros()))
+        .build();
+    let child = harness.spawn_child_collider(body, ball(), Vector::new(1.0, 0.0, 0.0));
+
+    harness.step();
+
+    let handle = harness.collider_handle(child).unwrap();
+    let physics_world = harness.world.read_resource::<PhysicsWorld<f32>>();
+    let position = physics_world.collider(handle).unwrap().position();
+    assert!((position.translation.vector - Vector::new(0.0, 1.0, 0.0)).norm() < EPSILON);
+}
+
+#[test]
+fn reparented_collider_is_attached_to_new_parent_body() {
+    let mut harness = PhysicsHarness::<f32>::new();
+    let body = harness.spawn_body(dynamic_body(), Vector::zeros());
+    let collider = harness.spawn_collider(ball(), Vector::zeros());
+    harness.step();
+    assert!(harness.collider_body(collider).unwrap().is_ground());
+
+    harness.insert(collider, Parent { entity: body });
+    harness.step();
+
+    assert_eq!(harness.collider_body(collider), harness.body_handle(body));
+}
+
+/// Returns the mass of the `RigidBody` of the given `Entity`.
+fn body_mass(harness: &PhysicsHarness<f32>, entity: Entity) -> f32 {
+    let handle = harness.body_handle(entity).unwrap();
+    harness
+        .world
+        .read_resource::<PhysicsWorld<f32>>()
+        .rigid_body(handle)
+        .unwrap()
+        .augmented_mass()
+        .linear
+}
+
+#[test]
+fn mass_from_colliders_follows_changed_colliders_only() {
+    let mut harness = PhysicsHarness::<f32>::new();
+    let body = harness.spawn_body(dynamic_body().mass_from_colliders(true), Vector::zeros());
+    harness.spawn_child_collider(body, ball().density(1.0), Vector::new(1.0, 0.0, 0.0));
+    harness.step();
+    let single_mass = body_mass(&harness, body);
+    assert!(single_mass > 0.0);
+
+    let second =
+        harness.spawn_child_collider(body, ball().density(1.0), Vector::new(-1.0, 0.0, 0.0));
+    harness.step();
+    assert!((body_mass(&harness, body) - 2.0 * single_mass).abs() < EPSILON);
+
+    // modifying the PhysicsBody keeps the derived mass
+    harness.modify(body, |physics_body: &mut PhysicsBody<f32>| {
+        physics_body.velocity = Vector::new(0.1, 0.0, 0.0);
+    });
+    harness.step();
+    assert!((body_mass(&harness, body) - 2.0 * single_mass).abs() < EPSILON);
+
+    harness.delete(second);
+    harness.step();
+    assert!((body_mass(&harness, body) - single_mass).abs() < EPSILON);
+}
+
+#[test]
+fn contact_filter_is_evaluated_before_every_step() {
+    let drop_through = Arc::new(AtomicBool::new(false));
+    let filter_drop_through = drop_through.clone();
+    let mut harness = PhysicsHarness::from_bundle(PhysicsBundle::default().with_contact_filter(
+        move |_: &ContactFilterPair<f32>| !filter_drop_through.load(Ordering::SeqCst),
+    ))
+    .with_gravity(Vector::new(0.0, -10.0, 0.0));
+
+    harness.spawn_collider(
+        PhysicsColliderBuilder::from(Shape::Rectangle(10.0, 1.0, 10.0)),
+        Vector::new(0.0, -1.0, 0.0),
+    );
+    let body = harness.spawn_body_with_collider(
+        dynamic_body().gravity_enabled(true),
+        ball(),
+        Vector::new(0.0, 0.5, 0.0),
+    );
+
+    // the body comes to rest on the platform
+    harness.run(60);
+    assert!(harness.translation(body).unwrap().y > -0.5);
+
+    // rejecting the resting pair lets the body drop through
+    drop_through.store(true, Ordering::SeqCst);
+    harness.run(60);
+    assert!(harness.translation(body).unwrap().y < -2.0);
+}
+
+#[test]
+fn resting_contact_reports_solver_impulse() {
+    let mut harness = PhysicsHarness::<f32>::new().with_gravity(Vector::new(0.0, -10.0, 0.0));
+    let ground = harness.spawn_collider(
+        PhysicsColliderBuilder::from(Shape::Rectangle(10.0, 1.0, 10.0)),
+        Vector::new(0.0, -1.0, 0.0),
+    );
+    let body = harness.spawn_body_with_collider(
+        dynamic_body().gravity_enabled(true),
+        ball(),
+        Vector::new(0.0, 0.5, 0.0),
+    );
+    harness.insert(body, Contacts::<f32>::default());
+
+    // the body comes to rest on the ground
+    harness.run(60);
+
+    // the impulses of the resting contact carry the weight of the body
+    let contacts = harness.world.read_storage::<Contacts<f32>>();
+    let contact = contacts.get(body).unwrap().get(ground).unwrap();
+    let impulse = contact
+        .points
+        .iter()
+        .map(|point| point.impulse)
+        .sum::<f32>();
+    assert!(impulse > 0.0, "impulse is {}", impulse);
+}
+
+#[test]
+fn ground_is_found_opposite_to_gravity_zone() {
+    let mut harness = PhysicsHarness::<f32>::new().with_gravity(Vector::new(0.0, -10.0, 0.0));
+
+    // a zone reversing the gravity with a ceiling to stand on
+    let zone = harness.spawn_collider(
+        PhysicsColliderBuilder::from(Shape::Rectangle(20.0, 20.0, 20.0)).sensor(true),
+        Vector::zeros(),
+    );
+    harness.insert(zone, GravityZone::directional(Vector::new(0.0, 10.0, 0.0)));
+    let ceiling = harness.spawn_collider(
+        PhysicsColliderBuilder::from(Shape::Rectangle(10.0, 1.0, 10.0)),
+        Vector::new(0.0, 3.0, 0.0),
+    );
+    let body = harness.spawn_body_with_collider(
+        dynamic_body().gravity_enabled(true),
+        ball(),
+        Vector::new(0.0, 1.5, 0.0),
+    );
+    harness.insert(body, GroundState::<f32>::new(0.5, 0.0));
+
+    harness.run(60);
+
+    let ground_states = harness.world.read_storage::<GroundState<f32>>();
+    let ground_state = ground_states.get(body).unwrap();
+    assert!(ground_state.is_grounded());
+    assert_eq!(ground_state.ground_entity(), Some(ceiling));
+}
@@ -0,0 +1,282 @@
+//! Round-trip tests for capturing and restoring a `PhysicsSnapshot`, run on
+//! the headless `PhysicsHarness`.
+//!
+//! ```bash
+//! $ cargo test --features test-utils
+//! ```
+
+use amethyst::{core::Parent, ecs::Entity};
+use game_physics::{
+    body::BodyStatus,
+    snapshot::BodySnapshot,
+    test_utils::{transform_at, PhysicsHarness},
+    PhysicsBody,
+    PhysicsBodyBuilder,
+    PhysicsCollider,
+    PhysicsColliderBuilder,
+    PhysicsSnapshot,
+    PhysicsWorld,
+    Shape,
+    Vector,
+};
+use ncollide3d::shape::Ball;
+
+const EPSILON: f32 = 0.0001;
+
+/// A serialisation round trip of a `PhysicsSnapshot`.
+type Format = fn(&PhysicsSnapshot<f32>) -> PhysicsSnapshot<f32>;
+
+fn via_bytes(snapshot: &PhysicsSnapshot<f32>) -> PhysicsSnapshot<f32> {
+    PhysicsSnapshot::from_bytes(&snapshot.to_bytes().unwrap()).unwrap()
+}
+
+fn via_ron(snapshot: &PhysicsSnapshot<f32>) -> PhysicsSnapshot<f32> {
+    PhysicsSnapshot::from_ron(&snapshot.to_ron().unwrap()).unwrap()
+}
+
+const FORMATS: [(&str, Format); 2] = [("bytes", via_bytes), ("ron", via_ron)];
+
+/// Creates a `PhysicsHarness` with gravity and a single, moving dynamic body.
+fn setup() -> (PhysicsHarness<f32>, Entity) {
+    let mut harness = PhysicsHarness::<f32>::new().with_gravity(Vector::new(0.0, -10.0, 0.0));
+    let entity = harness.spawn_body_with_collider(
+        PhysicsBodyBuilder::from(BodyStatus::Dynamic)
+            .mass(1.0)
+            .velocity(Vector::new(0.05, 0.0, 0.0)),
+        PhysicsColliderBuilder::from(Shape::Circle(0.5)),
+        Vector::new(0.0, 10.0, 0.0),
+    );
+    harness.run(5);
+    (harness, entity)
+}
+
+/// Returns the captured state of the `RigidBody` of the given `Entity`.
+fn body_state(harness: &PhysicsHarness<f32>, entity: Entity) -> BodySnapshot<f32> {
+    PhysicsSnapshot::<f32>::capture(&harness.world)
+        .bodies
+        .into_iter()
+        .find(|body| body.entity.id == entity.id())
+        .unwrap_or_else(|| panic!("{:?} has no RigidBody", entity))
+}
+
+fn assert_body_state(format: &str, actual: &BodySnapshot<f32>, expected: &BodySnapshot<f32>) {
+    let translation = actual.position.translation.vector - expected.position.translation.vector;
+    assert!(
+        translation.norm() <= EPSILON,
+        "{}: body is at {:?}, expected {:?}",
+        format,
+        actual.position,
+        expected.position
+    );
+    assert!(
+        (actual.rigid_body_velocity - expected.rigid_body_velocity).norm() <= EPSILON,
+        "{}: body moves with {:?}, expected {:?}",
+        format,
+        actual.rigid_body_velocity,
+        expected.rigid_body_velocity
+    );
+    assert_eq!(
+        actual.sleeping, expected.sleeping,
+        "{}: sleep state",
+        format
+    );
+}
+
+#[test]
+fn restored_body_continues_from_captured_state() {
+    for (format, round_trip) in FORMATS.iter() {
+        let (mut harness, entity) = setup();
+        let snapshot = round_trip(&PhysicsSnapshot::capture(&harness.world));
+        harness.step();
+        let expected = body_state(&harness, entity);
+
+        // mutate the simulation far beyond the captured state
+        harness.run(20);
+
+        snapshot.restore(&mut harness.world);
+        harness.step();
+
+        assert_body_state(format, &body_state(&harness, entity), &expected);
+    }
+}
+
+#[test]
+fn reinserted_body_continues_from_captured_state() {
+    for (format, round_trip) in FORMATS.iter() {
+        let (mut harness, entity) = setup();
+        let snapshot = round_trip(&PhysicsSnapshot::capture(&harness.world));
+        harness.step();
+        let expected = body_state(&harness, entity);
+
+        // remove the PhysicsBody, so its RigidBody has to be created from scratch
+        harness.run(20);
+        harness.remove::<PhysicsBody<f32>>(entity);
+        harness.step();
+        assert!(harness.body_handle(entity).is_none());
+
+        snapshot.restore(&mut harness.world);
+        harness.step();
+
+        assert_body_state(format, &body_state(&harness, entity), &expected);
+    }
+}
+
+#[test]
+fn reinserted_body_keeps_captured_sleep_state() {
+    for (format, round_trip) in FORMATS.iter() {
+        let mut harness = PhysicsHarness::<f32>::new();
+        let entity = harness.spawn_body(
+            PhysicsBodyBuilder::from(BodyStatus::Dynamic).mass(1.0),
+            Vector::zeros(),
+        );
+        harness.step();
+
+        let mut snapshot = PhysicsSnapshot::capture(&harness.world);
+        snapshot.bodies[0].sleeping = true;
+        let snapshot = round_trip(&snapshot);
+
+        harness.remove::<PhysicsBody<f32>>(entity);
+        harness.step();
+        snapshot.restore(&mut harness.world);
+        harness.step();
+
+        assert!(
+            body_state(&harness, entity).sleeping,
+            "{}: body is awake",
+            format
+        );
+    }
+}
+
+#[test]
+fn reinserted_collider_keeps_captured_layer() {
+    for (format, round_trip) in FORMATS.iter() {
+        let mut harness = PhysicsHarness::<f32>::new();
+        let entity = harness.spawn_collider(
+            PhysicsColliderBuilder::from(Shape::Circle(0.5))
+                .layer("player")
+                .collides_with(&["terrain"]),
+            Vector::zeros(),
+        );
+        harness.step();
+
+        let snapshot = round_trip(&PhysicsSnapshot::capture(&harness.world));
+        harness.remove::<PhysicsCollider<f32>>(entity);
+        harness.step();
+        snapshot.restore(&mut harness.world);
+
+        let physics_colliders = harness.world.read_storage::<PhysicsCollider<f32>>();
+        let physics_collider = physics_colliders.get(entity).unwrap();
+        assert_eq!(
+            physics_collider.layer,
+            Some("player".to_string()),
+            "{}",
+            format
+        );
+        assert_eq!(
+            physics_collider.collides_with,
+            Some(vec!["terrain".to_string()]),
+            "{}",
+            format
+        );
+    }
+}
+
+#[test]
+fn reinserted_collider_keeps_captured_material_name() {
+    for (format, round_trip) in FORMATS.iter() {
+        let mut harness = PhysicsHarness::<f32>::new();
+        let entity = harness.spawn_collider(
+            PhysicsColliderBuilder::from(Shape::Circle(0.5)).material_named("ice"),
+            Vector::zeros(),
+        );
+        harness.step();
+
+        let snapshot = round_trip(&PhysicsSnapshot::capture(&harness.world));
+        harness.remove::<PhysicsCollider<f32>>(entity);
+        harness.step();
+        snapshot.restore(&mut harness.world);
+
+        let physics_colliders = harness.world.read_storage::<PhysicsCollider<f32>>();
+        assert_eq!(
+            physics_colliders.get(entity).unwrap().material_name,
+            Some("ice".to_string()),
+            "{}",
+            format
+        );
+    }
+}
+
+#[test]
+fn restored_collider_rebuilds_changed_shape() {
+    for (format, round_trip) in FORMATS.iter() {
+        let mut harness = PhysicsHarness::<f32>::new();
+        let entity = harness.spawn_collider(
+            PhysicsColliderBuilder::from(Shape::Circle(0.5)),
+            Vector::zeros(),
+        );
+        harness.step();
+
+        let snapshot = round_trip(&PhysicsSnapshot::capture(&harness.world));
+        harness.remove::<PhysicsCollider<f32>>(entity);
+        harness.step();
+        harness.insert(
+            entity,
+            PhysicsColliderBuilder::<f32>::from(Shape::Circle(2.0)).build(),
+        );
+        harness.step();
+        snapshot.restore(&mut harness.world);
+        harness.step();
+
+        // the Collider in the PhysicsWorld is rebuilt with the captured shape
+        let handle = harness
+            .collider_handle(entity)
+            .unwrap_or_else(|| panic!("{}: {:?} has no Collider", format, entity));
+        let physics_world = harness.world.read_resource::<PhysicsWorld<f32>>();
+        let radius = physics_world
+            .collider(handle)
+            .unwrap()
+            .shape()
+            .as_shape::<Ball<f32>>()
+            .unwrap()
+            .radius();
+        assert!(
+            (radius - 0.5).abs() <= EPSILON,
+            "{}: radius is {}",
+            format,
+            radius
+        );
+    }
+}
+
+#[test]
+fn restored_child_body_keeps_its_local_transform() {
+    for (format, round_trip) in FORMATS.iter() {
+        let mut harness = PhysicsHarness::<f32>::new();
+        let parent = harness
+            .create_entity()
+            .with(transform_at(&Vector::new(0.0, 5.0, 0.0)))
+            .build();
+        let child = harness
+            .create_entity()
+            .with(PhysicsBodyBuilder::<f32>::from(BodyStatus::Kinematic).build())
+            .with(transform_at(&Vector::new(1.0, 0.0, 0.0)))
+            .with(Parent { entity: parent })
+            .build();
+        harness.step();
+
+        let snapshot = round_trip(&PhysicsSnapshot::capture(&harness.world));
+        harness.insert(child, transform_at(&Vector::new(3.0, 0.0, 0.0)));
+        harness.step();
+        snapshot.restore(&mut harness.world);
+
+        // the captured world space position is restored relative to the parent
+        let translation = harness.translation(child).unwrap();
+        assert!(
+            (translation - Vector::new(1.0, 0.0, 0.0)).norm() <= EPSILON,
+            "{}: child is at {:?}",
+            format,
+            translation
+        );
+    }
+}